@@ -0,0 +1,201 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    disconnect::DisconnectEvent,
+    ecs::prelude::*,
+    player::GameProfileComponent,
+    swarm::Swarm,
+};
+
+use crate::prelude::*;
+
+pub const ACCOUNT_PREFIX: &str = "Account Command: ";
+
+/// Add or remove accounts from the swarm at runtime.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AccountCommandPlugin;
+
+impl Cmd for AccountCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["account", "accounts"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Add or remove accounts from the swarm at runtime"
+    }
+}
+
+impl Plugin for AccountCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_account_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl AccountCommandPlugin {
+    pub fn handle_account_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        mut disconnect_events: MessageWriter<DisconnectEvent>,
+        query: Query<(Entity, &GameProfileComponent, &LocalSettings)>,
+        global_settings: Res<GlobalSettings>,
+        login_queue: Option<Res<LoginQueue>>,
+        swarm: Res<Swarm>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Account(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let mut msg_event = MsgEvent {
+                content: String::new(),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            if !event.sender.is_admin(&global_settings) {
+                msg_event.content = str!("You must be an admin to use this command");
+                msg_event.status = 403;
+                msg_events.write(msg_event);
+                continue;
+            }
+
+            let mut args = event.args.clone();
+            let (Some(action), Some(username)) = (args.pop_front(), args.pop_front()) else {
+                let accounts = query.iter().map(|(_, profile, _)| profile.name.as_str());
+                let accounts = accounts.collect::<Vec<_>>().join(", ");
+                msg_event.content =
+                    format!("Usage: account add|remove <name> | Online: {accounts}");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let existing = query
+                .iter()
+                .find(|(_, profile, _)| profile.name.eq_ignore_ascii_case(&username));
+
+            (msg_event.status, msg_event.content) = match action.as_str() {
+                "add" => {
+                    let auth_mode = match args.pop_front().as_deref() {
+                        None | Some("offline") => AuthMode::Offline,
+                        Some("online" | "microsoft") => AuthMode::Online,
+                        Some(auth_mode) => {
+                            msg_event.content =
+                                format!("Invalid auth mode: {auth_mode} | Modes: offline & online");
+                            msg_event.status = 406;
+                            msg_events.write(msg_event);
+                            continue;
+                        }
+                    };
+
+                    let Some(login_queue) = login_queue.as_deref().cloned() else {
+                        msg_event.content = str!("Accounts are still loading, try again later");
+                        msg_event.status = 503;
+                        msg_events.write(msg_event);
+                        continue;
+                    };
+
+                    Self::add_account(
+                        &swarm,
+                        login_queue,
+                        existing.is_some(),
+                        &username,
+                        auth_mode,
+                    )
+                }
+                "remove" | "delete" => {
+                    if let Some((entity, _, _)) = existing {
+                        disconnect_events.write(DisconnectEvent {
+                            entity,
+                            reason: Some(format!("{ACCOUNT_PREFIX}{}", event.sender).into()),
+                        });
+                    }
+
+                    Self::remove_account(existing.map(|(_, _, settings)| settings), &username)
+                }
+                _ => (406, str!("Invalid action | Actions: add & remove")),
+            };
+
+            msg_events.write(msg_event);
+        }
+    }
+
+    fn add_account(
+        swarm: &Swarm,
+        login_queue: LoginQueue,
+        online: bool,
+        username: &str,
+        auth_mode: AuthMode,
+    ) -> (u16, String) {
+        if !is_valid_username(username) {
+            return (406, format!("Invalid username: {username}"));
+        }
+
+        let settings = match LocalSettings::new(username) {
+            Ok(settings) if online || settings.file_path().exists() => {
+                return (409, format!("{username} already exists"));
+            }
+            Ok(mut settings) => {
+                settings.auth_mode = auth_mode;
+                match settings.save() {
+                    Ok(settings) => settings,
+                    Err(error) => return (500, format!("Failed to save settings: {error}")),
+                }
+            }
+            Err(error) => return (500, format!("Failed to create settings: {error}")),
+        };
+
+        let swarm = swarm.clone();
+        let username = str!(username);
+        let content = format!("Adding {username}...");
+        tokio::task::spawn_local(async move {
+            if let Err(error) = login_queue.add_account(swarm, settings).await {
+                error!("[{username}] There was an error adding the account: {error}");
+            }
+        });
+
+        (202, content)
+    }
+
+    fn remove_account(settings: Option<&LocalSettings>, username: &str) -> (u16, String) {
+        if !is_valid_username(username) {
+            return (406, format!("Invalid username: {username}"));
+        }
+
+        let path = match settings {
+            Some(settings) => settings.file_path().to_path_buf(),
+            None => match LocalSettings::new(username) {
+                Ok(settings) => settings.file_path().to_path_buf(),
+                Err(error) => return (500, format!("Failed to find settings: {error}")),
+            },
+        };
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => (200, format!("Removed {username}")),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                (404, format!("{username} doesn't exist"))
+            }
+            Err(error) => (500, format!("Failed to remove settings: {error}")),
+        }
+    }
+}
+
+/// Check a Minecraft username before it's used as a local settings file name.
+#[must_use]
+pub fn is_valid_username(username: &str) -> bool {
+    (3..=16).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,111 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+    player::GameProfileComponent,
+};
+
+use crate::prelude::*;
+
+/// Get or set an account's local settings at runtime.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ConfigCommandPlugin;
+
+impl Cmd for ConfigCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["config", "cfg"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Get or set an account's local settings at runtime"
+    }
+}
+
+impl Plugin for ConfigCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_config_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl ConfigCommandPlugin {
+    pub fn handle_config_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        mut query: Query<(&GameProfileComponent, &mut LocalSettings)>,
+        global_settings: Res<GlobalSettings>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Config(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let mut msg_event = MsgEvent {
+                content: String::new(),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            if !event.sender.is_admin(&global_settings) {
+                msg_event.content = str!("You must be an admin to use this command");
+                msg_event.status = 403;
+                msg_events.write(msg_event);
+                continue;
+            }
+
+            let mut args = event.args.clone();
+            let (Some(bot_name), Some(action), Some(path)) =
+                (args.pop_front(), args.pop_front(), args.pop_front())
+            else {
+                msg_event.content = str!("Usage: config <bot> get|set <path> [value]");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let Some((_, mut local_settings)) = query
+                .iter_mut()
+                .find(|(profile, _)| profile.name.eq_ignore_ascii_case(&bot_name))
+            else {
+                msg_event.content = format!("Bot not found: {bot_name}");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let result = match action.as_str() {
+                "get" => local_settings.get_value(&path),
+                "set" => {
+                    let raw = args.into_iter().collect::<Vec<_>>().join(" ");
+                    local_settings.set_value(&path, &raw).and_then(|value| {
+                        *local_settings = local_settings.clone().save()?;
+                        Ok(value)
+                    })
+                }
+                _ => {
+                    msg_event.content = str!("Invalid action | Actions: get & set");
+                    msg_event.status = 406;
+                    msg_events.write(msg_event);
+                    continue;
+                }
+            };
+
+            (msg_event.status, msg_event.content) = match result {
+                Ok(value) => (200, format!("{bot_name} {path} = {value}")),
+                Err(error) => (406, error.to_string()),
+            };
+
+            msg_events.write(msg_event);
+        }
+    }
+}
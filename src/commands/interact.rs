@@ -17,6 +17,10 @@ impl Cmd for InteractCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["interact"]
     }
+
+    fn description(&self) -> &'static str {
+        "Send an interact block packet at the given coordinates"
+    }
 }
 
 impl Plugin for InteractCommandPlugin {
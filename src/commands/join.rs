@@ -1,6 +1,7 @@
 use azalea::{
     app::{App, Plugin, Update},
     ecs::prelude::*,
+    player::GameProfileComponent,
 };
 
 use crate::prelude::*;
@@ -13,6 +14,10 @@ impl Cmd for JoinCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["connect", "join", "c"]
     }
+
+    fn description(&self) -> &'static str {
+        "Connect an account to the server by enabling AutoReconnect"
+    }
 }
 
 impl Plugin for JoinCommandPlugin {
@@ -31,6 +36,8 @@ impl JoinCommandPlugin {
     pub fn handle_join_cmd_events(
         mut cmd_events: MessageReader<CmdEvent>,
         mut msg_events: MessageWriter<MsgEvent>,
+        mut zenith_events: MessageWriter<ZenithEvent>,
+        query: Query<(Entity, &GameProfileComponent, &LocalSettings)>,
         swarm_state: Res<SwarmState>,
     ) {
         if let Some(event) = cmd_events.read().next() {
@@ -55,11 +62,30 @@ impl JoinCommandPlugin {
 
             msg_event.content = format!("[202] Enabling AutoReconnect for {bot_name}");
             msg_event.status = 202;
+
+            /* Online bots behind ZenithProxy only need ZenithProxy to connect */
+            if let Some((entity, _, _)) = query.iter().find(|(_, profile, settings)| {
+                profile.name.eq_ignore_ascii_case(bot_name) && settings.zenith_proxy.enabled
+            }) {
+                msg_event.content = format!("[202] Connecting {bot_name} through ZenithProxy");
+                zenith_events.write_batch([
+                    ZenithEvent {
+                        entity,
+                        action: ZenithAction::AutoReconnect(true),
+                    },
+                    ZenithEvent {
+                        entity,
+                        action: ZenithAction::Connect,
+                    },
+                ]);
+            }
+
             msg_events.write(msg_event);
-            swarm_state
-                .auto_reconnect
-                .write()
-                .insert(bot_name.to_lowercase(), (true, 0));
+            let mut auto_reconnect = swarm_state.auto_reconnect.write();
+            let record = auto_reconnect.entry(bot_name.to_lowercase()).or_default();
+            record.attempts = 0;
+            record.enabled = true;
+            record.resume_at = None;
         }
 
         cmd_events.clear();
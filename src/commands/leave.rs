@@ -17,6 +17,10 @@ impl Cmd for LeaveCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["leave", "disconnect", "dc"]
     }
+
+    fn description(&self) -> &'static str {
+        "Disconnect an account from the server and disable AutoReconnect"
+    }
 }
 
 impl Plugin for LeaveCommandPlugin {
@@ -36,7 +40,9 @@ impl LeaveCommandPlugin {
         mut cmd_events: MessageReader<CmdEvent>,
         mut msg_events: MessageWriter<MsgEvent>,
         mut disconnect_events: MessageWriter<DisconnectEvent>,
-        query: Query<(Entity, &GameProfileComponent)>,
+        mut zenith_events: MessageWriter<ZenithEvent>,
+        mut commands: Commands,
+        query: Query<(Entity, &GameProfileComponent, &LocalSettings)>,
     ) {
         for event in cmd_events.read().cloned() {
             let Cmds::Leave(_plugin) = event.cmd else {
@@ -58,7 +64,9 @@ impl LeaveCommandPlugin {
                 continue;
             };
 
-            let Some((entity, profile)) = query.iter().find(|(_, p)| p.name == bot_name) else {
+            let Some((entity, profile, settings)) =
+                query.iter().find(|(_, p, _)| p.name == bot_name)
+            else {
                 msg_event.content = str!("offline bot name");
                 msg_event.status = 404;
                 msg_events.write(msg_event);
@@ -75,6 +83,23 @@ impl LeaveCommandPlugin {
                 continue; /* Not this account */
             }
 
+            if settings.zenith_proxy.enabled {
+                /* ZenithProxy disconnects us after it leaves the server */
+                commands.entity(entity).insert(ZenithLeave);
+                zenith_events.write_batch([
+                    ZenithEvent {
+                        entity,
+                        action: ZenithAction::AutoReconnect(false),
+                    },
+                    ZenithEvent {
+                        entity,
+                        action: ZenithAction::Disconnect,
+                    },
+                ]);
+
+                continue;
+            }
+
             disconnect_events.write(DisconnectEvent {
                 entity,
                 reason: Some(format!("{LEAVE_PREFIX}{:?}", event.sender).into()),
@@ -1,9 +1,16 @@
+pub mod account;
+pub mod config;
 pub mod interact;
 pub mod join;
 pub mod leave;
+pub mod module;
+pub mod notify;
 pub mod pearl;
 pub mod playtime;
+pub mod queue;
 pub mod seen;
+pub mod status;
+pub mod verify;
 pub mod whitelist;
 
 use azalea::app::{PluginGroup, PluginGroupBuilder};
@@ -15,12 +22,19 @@ pub struct CommandsPluginGroup;
 impl PluginGroup for CommandsPluginGroup {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(AccountCommandPlugin)
+            .add(ConfigCommandPlugin)
             .add(InteractCommandPlugin)
             .add(JoinCommandPlugin)
             .add(LeaveCommandPlugin)
+            .add(ModuleCommandPlugin)
+            .add(NotifyCommandPlugin)
             .add(PearlCommandPlugin)
             .add(PlaytimeCommandPlugin)
+            .add(QueueCommandPlugin)
             .add(SeenCommandPlugin)
+            .add(StatusCommandPlugin)
+            .add(VerifyCommandPlugin)
             .add(WhitelistCommandPlugin)
     }
 }
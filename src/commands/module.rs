@@ -0,0 +1,154 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+    player::GameProfileComponent,
+};
+
+use crate::prelude::*;
+
+/// Toggle an account's modules on or off at runtime.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ModuleCommandPlugin;
+
+impl Cmd for ModuleCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["module", "modules"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Toggle an account's modules on or off at runtime"
+    }
+}
+
+impl Plugin for ModuleCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_module_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl ModuleCommandPlugin {
+    pub fn handle_module_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        mut query: Query<(&GameProfileComponent, &mut LocalSettings)>,
+        global_settings: Res<GlobalSettings>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Module(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let mut msg_event = MsgEvent {
+                content: String::new(),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            if !event.sender.is_admin(&global_settings) {
+                msg_event.content = str!("You must be an admin to use this command");
+                msg_event.status = 403;
+                msg_events.write(msg_event);
+                continue;
+            }
+
+            let mut args = event.args.clone();
+            let Some(bot_name) = args.pop_front() else {
+                msg_event.content = str!("Usage: module <bot> [name] [on|off]");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let Some((_, mut local_settings)) = query
+                .iter_mut()
+                .find(|(profile, _)| profile.name.eq_ignore_ascii_case(&bot_name))
+            else {
+                msg_event.content = format!("Bot not found: {bot_name}");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let modules = find_modules(&local_settings);
+            let Some(name) = args.pop_front() else {
+                let modules = modules
+                    .iter()
+                    .map(|(name, enabled)| {
+                        format!("{name}: {}", if *enabled { "on" } else { "off" })
+                    })
+                    .collect::<Vec<_>>();
+
+                msg_event.content = format!("Modules | {}", modules.join(", "));
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let Some((module, _)) = modules
+                .iter()
+                .find(|(module, _)| normalize(module) == normalize(&name))
+            else {
+                let names = modules.iter().map(|(name, _)| name.as_str());
+                msg_event.content = format!(
+                    "Invalid module | Modules: {}",
+                    names.collect::<Vec<_>>().join(", ")
+                );
+                msg_event.status = 406;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let enabled = match args.pop_front().as_deref() {
+                Some("on" | "true" | "enable") => "true",
+                Some("off" | "false" | "disable") => "false",
+                _ => {
+                    msg_event.content = str!("Invalid state | States: on & off");
+                    msg_event.status = 406;
+                    msg_events.write(msg_event);
+                    continue;
+                }
+            };
+
+            let path = format!("{module}.enabled");
+            (msg_event.status, msg_event.content) = match local_settings.set_value(&path, enabled) {
+                Err(error) => (406, error.to_string()),
+                Ok(_) => match local_settings.clone().save() {
+                    Ok(saved) => {
+                        *local_settings = saved;
+                        (200, format!("{bot_name} {module} = {enabled}"))
+                    }
+                    Err(error) => (500, error.to_string()),
+                },
+            };
+
+            msg_events.write(msg_event);
+        }
+    }
+}
+
+/// Find every settings table with an `enabled` flag, these are the toggleable modules.
+fn find_modules(local_settings: &LocalSettings) -> Vec<(String, bool)> {
+    let Ok(toml::Value::Table(table)) = toml::Value::try_from(local_settings) else {
+        return Vec::new();
+    };
+
+    table
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.get("enabled")?.as_bool()?)))
+        .collect()
+}
+
+fn normalize(name: &str) -> String {
+    name.replace(['_', '-'], "").to_lowercase()
+}
@@ -0,0 +1,114 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+};
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+/// Opt in or out of stasis chamber notifications in Discord DMs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NotifyCommandPlugin;
+
+impl Cmd for NotifyCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["notify", "notifications"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Opt in or out of stasis chamber notifications in Discord DMs"
+    }
+}
+
+impl Plugin for NotifyCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_notify_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl NotifyCommandPlugin {
+    /// # Panics
+    /// Will panic if `GlobalSettings::save` fails.
+    pub fn handle_notify_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        mut global_settings: ResMut<GlobalSettings>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Notify(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let mut msg_event = MsgEvent {
+                content: String::new(),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            /* Every linked account of the sender */
+            let uuids = global_settings
+                .users
+                .iter()
+                .filter(|(_, user)| !user.discord_id.is_empty())
+                .filter(|(uuid, user)| match event.sender {
+                    CmdSender::Console => false,
+                    CmdSender::Discord(user_id) => user.discord_id == user_id.to_string(),
+                    CmdSender::Minecraft(sender) => **uuid == sender,
+                })
+                .map(|(uuid, _)| *uuid)
+                .collect::<Vec<Uuid>>();
+
+            if uuids.is_empty() {
+                msg_event.content = str!("Link your Discord account to receive notifications");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            }
+
+            let enabled = uuids
+                .iter()
+                .all(|uuid| global_settings.dm_notifications.contains(uuid));
+
+            let enable = match event.args.front().map(String::as_str) {
+                Some("on") => true,
+                Some("off") => false,
+                None => !enabled,
+                Some(state) => {
+                    msg_event.content = format!("Invalid state: {state} | States: on, off");
+                    msg_event.status = 406;
+                    msg_events.write(msg_event);
+                    continue;
+                }
+            };
+
+            global_settings
+                .dm_notifications
+                .retain(|uuid| !uuids.contains(uuid));
+
+            if enable {
+                global_settings.dm_notifications.extend(uuids);
+            }
+
+            global_settings.save().expect("Failed to save settings");
+            msg_event.content = if enable {
+                str!("Stasis chamber notifications will be sent to your Discord DMs")
+            } else {
+                str!("Stasis chamber notifications disabled")
+            };
+
+            msg_events.write(msg_event);
+        }
+    }
+}
@@ -1,10 +1,14 @@
+use std::collections::VecDeque;
+
 use azalea::{
     app::{App, Plugin, Update},
     ecs::prelude::*,
     entity::Position,
     local_player::TabList,
+    pathfinder::StopPathfindingEvent,
     BlockPos,
 };
+use uuid::Uuid;
 
 use crate::prelude::*;
 
@@ -16,6 +20,10 @@ impl Cmd for PearlCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["pearl", "tp", "teleport", "warp", "home"]
     }
+
+    fn description(&self) -> &'static str {
+        "Automatically pull the closest stasis chamber at a location"
+    }
 }
 
 impl Plugin for PearlCommandPlugin {
@@ -32,12 +40,13 @@ impl Plugin for PearlCommandPlugin {
 }
 
 impl PearlCommandPlugin {
-    #[allow(clippy::too_many_lines)]
-    #[cfg_attr(not(feature = "bot"), allow(unused_variables))]
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
     pub fn handle_pearl_cmd_events(
         mut cmd_events: MessageReader<CmdEvent>,
         mut msg_events: MessageWriter<MsgEvent>,
         mut pearl_events: MessageWriter<PearlGotoEvent>,
+        mut stop_pathfinding_events: MessageWriter<StopPathfindingEvent>,
+        mut resend_pearl_events: ResMut<ResendPearlEvents>,
         query: Query<(&TabList, &Position, &LocalSettings)>,
         settings: Query<&LocalSettings>,
         global_settings: Res<GlobalSettings>,
@@ -54,8 +63,10 @@ impl PearlCommandPlugin {
 
             let mut locations = settings
                 .iter()
-                .cloned()
-                .map(|ls| ls.auto_pearl.location)
+                .map(|ls| match ls.server.as_str() {
+                    "" => ls.auto_pearl.location.clone(),
+                    server => format!("{server}:{}", ls.auto_pearl.location),
+                })
                 .collect::<Vec<_>>();
 
             locations.sort();
@@ -67,19 +78,35 @@ impl PearlCommandPlugin {
                 source:  event.source.clone(),
                 sender:  event.sender,
                 status:  406,
-                content: format!("Invalid location | Locations: {}", locations.join(", ")),
+                content: format!(
+                    "Invalid location | Locations: {}",
+                    paginate(&locations, ", ", 1, global_settings.page_size)
+                ),
             };
 
             if !local_settings.auto_pearl.enabled {
                 continue; /* Auto Pearl Disabled */
             }
 
-            #[allow(clippy::infallible_destructuring_match)]
+            if is_locations_list(&event.args) {
+                let page = event.args.get(1).and_then(|page| page.parse().ok());
+                let page_size = global_settings.page_size;
+                let page = paginate(&locations, ", ", page.unwrap_or(1), page_size);
+
+                msg_event.status = 200;
+                msg_event.content = format!("Locations: {page}");
+                msg_events.write(msg_event);
+                cmd_events.clear();
+                return;
+            }
+
+            let confirmed = event.args.back().is_some_and(|arg| arg == "confirm");
+            if confirmed {
+                event.args.pop_back();
+            }
+
             let uuid = match event.sender {
-                #[cfg(feature = "api")]
-                CmdSender::ApiServer(uuid) => uuid,
-                #[cfg(feature = "bot")]
-                CmdSender::Discord(user_id) => {
+                CmdSender::Console | CmdSender::Discord(_) => {
                     let Some(username) = event.args.pop_front() else {
                         msg_event.status = 404;
                         msg_event.content = str!("Missing player name");
@@ -92,6 +119,8 @@ impl PearlCommandPlugin {
                         info.profile.name.to_lowercase() == username.to_lowercase()
                     }) {
                         *uuid
+                    } else if let Ok(uuid) = Uuid::parse_str(&username) {
+                        uuid /* Autocompleted Offline Player */
                     } else {
                         match fetch_uuid(&username.to_lowercase()) {
                             Ok(uuid) => uuid,
@@ -105,7 +134,9 @@ impl PearlCommandPlugin {
                         }
                     };
 
-                    if global_settings.whitelist_only {
+                    if let CmdSender::Discord(user_id) = event.sender
+                        && global_settings.whitelist_only
+                    {
                         let Some(user) = global_settings.users.get(&uuid) else {
                             cmd_events.clear();
                             return; /* Not Whitelisted */
@@ -123,19 +154,42 @@ impl PearlCommandPlugin {
 
                     uuid
                 }
-                #[allow(irrefutable_let_patterns)]
                 CmdSender::Minecraft(uuid) => uuid,
             };
 
+            if event.args.front().is_some_and(|arg| arg == "cancel") {
+                /* Only stop walking if it's to this owner's chamber, not another player's */
+                let is_active = resend_pearl_events.is_active(entity, uuid);
+                if resend_pearl_events.cancel(entity, uuid) {
+                    if is_active {
+                        stop_pathfinding_events.write(StopPathfindingEvent {
+                            entity,
+                            force: false,
+                        });
+                    }
+
+                    msg_event.status = 200;
+                    msg_event.content = str!("Cancelled your pearl");
+                } else if event.source.in_game() {
+                    msg_event.status = 404;
+                    msg_event.content = str!("You don't have a pearl on the way");
+                } else {
+                    continue; /* Next Bot */
+                }
+
+                msg_events.write(msg_event);
+                cmd_events.clear();
+                return;
+            }
+
             let local_settings = match settings.first() {
                 Some(local_settings) if settings.len() == 1 => local_settings,
                 _ => {
                     /* Multi-Account Swarm */
                     if let Some(location) = event.args.pop_front() {
-                        #[allow(irrefutable_let_patterns)]
-                        if location == local_settings.auto_pearl.location {
+                        if local_settings.is_location(&location) {
                             local_settings
-                        } else if let CmdSource::Minecraft(_) = event.source {
+                        } else if event.source.in_game() {
                             if event.message {
                                 // TODO: Redirect to appropriate bot
                                 local_settings /* Local Chat */
@@ -143,24 +197,14 @@ impl PearlCommandPlugin {
                                 continue; /* Global Chat */
                             }
                         } else {
-                            continue; /* Discord Chat */
+                            continue; /* Remote Chat */
                         }
+                    } else if event.source.in_game() {
+                        local_settings
                     } else {
-                        match event.source {
-                            #[cfg(feature = "api")]
-                            CmdSource::ApiServer(_) => {
-                                msg_events.write(msg_event);
-                                cmd_events.clear();
-                                return;
-                            }
-                            #[cfg(feature = "bot")]
-                            CmdSource::Discord(_) => {
-                                msg_events.write(msg_event);
-                                cmd_events.clear();
-                                return;
-                            }
-                            CmdSource::Minecraft(_) => local_settings,
-                        }
+                        msg_events.write(msg_event);
+                        cmd_events.clear();
+                        return;
                     }
                 }
             };
@@ -170,7 +214,9 @@ impl PearlCommandPlugin {
                 .0
                 .values()
                 .filter(|&chamber| chamber.owner_uuid == uuid)
-                .filter(|&chamber| chamber.location == local_settings.auto_pearl.location)
+                .filter(|&chamber| {
+                    chamber.is_at(&local_settings.server, &local_settings.auto_pearl.location)
+                })
                 .cloned()
                 .map(|chamber| {
                     let distance = 1
@@ -181,19 +227,22 @@ impl PearlCommandPlugin {
                     (chamber, distance)
                 });
 
+            let shared_count = |chamber: &StasisChamber| {
+                stasis_chambers
+                    .0
+                    .values()
+                    .filter(|c| c.block_pos == chamber.block_pos)
+                    .filter(|c| c.server == chamber.server)
+                    .filter(|c| c.owner_uuid != chamber.owner_uuid)
+                    .count()
+            };
+
             let count = player_chambers.clone().count().saturating_sub(1);
             let Some((chamber, _distance)) = player_chambers
                 .filter(|(_, distance)| *distance <= global_settings.pearl_view_distance * 4)
                 .min_by_key(|(chamber, distance)| {
-                    let shared_count = stasis_chambers
-                        .0
-                        .values()
-                        .filter(|c| c.block_pos == chamber.block_pos)
-                        .filter(|c| c.owner_uuid != chamber.owner_uuid)
-                        .count();
-
                     // First compare by shared count, then by distance
-                    (shared_count, *distance)
+                    (shared_count(chamber), *distance)
                 })
             else {
                 let location = &local_settings.auto_pearl.location;
@@ -204,6 +253,19 @@ impl PearlCommandPlugin {
                 return;
             };
 
+            let shared = shared_count(&chamber);
+            if shared > 0 && event.source.interactive() && !confirmed {
+                msg_event.status = 409;
+                msg_event.content = match shared {
+                    1 => str!("That chamber is shared with another player, confirm to pull it"),
+                    s => format!("That chamber is shared with {s} players, confirm to pull it"),
+                };
+
+                msg_events.write(msg_event);
+                cmd_events.clear();
+                return;
+            }
+
             msg_event.status = 200;
             msg_event.content = match count {
                 0 => str!("I'm on my way, this was your last pearl!"),
@@ -224,3 +286,14 @@ impl PearlCommandPlugin {
         }
     }
 }
+
+/// Check if the arguments are exactly `locations [page]`, so a player named `locations` can
+/// still be pulled with `pearl locations <location>` from Discord or the console.
+#[must_use]
+pub fn is_locations_list(args: &VecDeque<String>) -> bool {
+    match args.len() {
+        1 => args[0] == "locations",
+        2 => args[0] == "locations" && args[1].parse::<usize>().is_ok(),
+        _ => false,
+    }
+}
@@ -17,6 +17,10 @@ impl Cmd for PlaytimeCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["playtime"]
     }
+
+    fn description(&self) -> &'static str {
+        "Fetch a players play time using 2b2t.vc"
+    }
 }
 
 impl Plugin for PlaytimeCommandPlugin {
@@ -0,0 +1,98 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+    player::GameProfileComponent,
+};
+
+use crate::prelude::*;
+
+/// Show the queue position and ETA of each account.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct QueueCommandPlugin;
+
+impl Cmd for QueueCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["queue", "q"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the queue position and ETA of each account"
+    }
+}
+
+impl Plugin for QueueCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_queue_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl QueueCommandPlugin {
+    pub fn handle_queue_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        mut zenith_events: MessageWriter<ZenithEvent>,
+        query: Query<(
+            Entity,
+            &GameProfileComponent,
+            &LocalSettings,
+            Option<&QueueStatus>,
+            Option<&ZenithStatus>,
+        )>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Queue(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let filter = event.args.front();
+            let mut statuses = Vec::new();
+            for (entity, profile, settings, queue_status, zenith_status) in &query {
+                if filter.is_some_and(|name| !profile.name.eq_ignore_ascii_case(name)) {
+                    continue;
+                }
+
+                if settings.zenith_proxy.enabled {
+                    zenith_events.write(ZenithEvent {
+                        entity,
+                        action: ZenithAction::Queue, /* Refresh for next time */
+                    });
+                }
+
+                let name = &profile.name;
+                let zenith_queue = zenith_status.and_then(|status| status.queue.as_ref());
+                statuses.push(match (queue_status, zenith_queue) {
+                    (Some(queue_status), _) => format!("{name}: {queue_status}"),
+                    (None, Some(zenith_queue)) => format!("{name}: {zenith_queue}"),
+                    (None, None) => format!("{name}: not in queue"),
+                });
+            }
+
+            statuses.sort();
+
+            let mut msg_event = MsgEvent {
+                content: statuses.join(" | "),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            if statuses.is_empty() {
+                msg_event.content = str!("Bot not found");
+                msg_event.status = 404;
+            }
+
+            msg_events.write(msg_event);
+        }
+    }
+}
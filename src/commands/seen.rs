@@ -18,6 +18,10 @@ impl Cmd for SeenCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["seen"]
     }
+
+    fn description(&self) -> &'static str {
+        "Fetch a players first and last seen time using 2b2t.vc"
+    }
 }
 
 impl Plugin for SeenCommandPlugin {
@@ -0,0 +1,139 @@
+use std::time::SystemTime;
+
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+    player::GameProfileComponent,
+};
+
+use crate::prelude::*;
+
+/// Show which accounts are online and their reconnect attempts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StatusCommandPlugin;
+
+impl Cmd for StatusCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["status", "bots"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Show which accounts are online and their reconnect attempts"
+    }
+}
+
+impl Plugin for StatusCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_status_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl StatusCommandPlugin {
+    pub fn handle_status_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        query: Query<(
+            &GameProfileComponent,
+            Option<&QueueStatus>,
+            Option<&ZenithStatus>,
+        )>,
+        global_settings: Res<GlobalSettings>,
+        swarm_state: Res<SwarmState>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Status(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let page = event.args.front().and_then(|arg| arg.parse::<usize>().ok());
+            let filter = event
+                .args
+                .front()
+                .filter(|_| page.is_none())
+                .map(|name| name.to_lowercase());
+
+            let mut bot_names = query
+                .iter()
+                .map(|(profile, _, _)| profile.name.to_lowercase())
+                .chain(swarm_state.auto_reconnect.read().keys().cloned())
+                .filter(|name| filter.as_ref().is_none_or(|filter| filter == name))
+                .collect::<Vec<_>>();
+
+            bot_names.sort();
+            bot_names.dedup();
+
+            let online = query
+                .iter()
+                .map(|(profile, queue_status, zenith_status)| {
+                    (profile.name.to_lowercase(), (queue_status, zenith_status))
+                })
+                .collect::<HashMap<_, _>>();
+
+            let max_attempts = global_settings.reconnect.max_attempts;
+            let auto_reconnect = swarm_state.auto_reconnect.read();
+            let statuses = bot_names
+                .iter()
+                .map(|name| {
+                    let record = auto_reconnect.get(name).cloned().unwrap_or_default();
+                    let attempts = if max_attempts > 0 {
+                        format!("{}/{max_attempts}", record.attempts)
+                    } else {
+                        format!("{}", record.attempts)
+                    };
+
+                    if let Some((queue_status, zenith_status)) = online.get(name) {
+                        let mut status = match queue_status {
+                            Some(queue_status) => format!("{name}: in queue, {queue_status}"),
+                            None => format!("{name}: online"),
+                        };
+
+                        if let Some(zenith_status) = zenith_status {
+                            status = format!("{status}, {zenith_status}");
+                        }
+
+                        return status;
+                    }
+
+                    if !record.enabled {
+                        return format!("{name}: offline ({})", record.reason);
+                    }
+
+                    let secs = record
+                        .resume_at
+                        .and_then(|resume_at| resume_at.duration_since(SystemTime::now()).ok())
+                        .map_or(0, |duration| duration.as_secs());
+
+                    format!(
+                        "{name}: reconnecting in {secs}s, attempt {attempts} ({})",
+                        record.reason
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let mut msg_event = MsgEvent {
+                content: paginate(&statuses, " | ", page.unwrap_or(1), global_settings.page_size),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            if statuses.is_empty() {
+                msg_event.content = str!("Bot not found");
+                msg_event.status = 404;
+            }
+
+            msg_events.write(msg_event);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+};
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+/// Verify a one-time code to link Discord and Minecraft accounts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VerifyCommandPlugin;
+
+impl Cmd for VerifyCommandPlugin {
+    fn aliases(&self) -> Vec<&'static str> {
+        vec!["verify"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Verify a one-time code to link Discord and Minecraft accounts"
+    }
+}
+
+impl Plugin for VerifyCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            Self::handle_verify_cmd_events
+                .ambiguous_with_all()
+                .before(MinecraftParserPlugin::handle_send_msg_events)
+                .after(MinecraftParserPlugin::handle_chat_received_events),
+        );
+    }
+}
+
+impl VerifyCommandPlugin {
+    /// # Panics
+    /// Will panic if `GlobalSettings::save` fails.
+    pub fn handle_verify_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageWriter<MsgEvent>,
+        mut settings: ResMut<GlobalSettings>,
+        mut link_codes: ResMut<LinkCodes>,
+    ) {
+        for event in cmd_events.read() {
+            let Cmds::Verify(_plugin) = event.cmd else {
+                continue;
+            };
+
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let mut msg_event = MsgEvent {
+                content: String::new(),
+                entity:  event.entity,
+                sender:  event.sender,
+                source:  event.source.clone(),
+                status:  200,
+            };
+
+            let Some(code) = event.args.front() else {
+                msg_event.content = str!("Missing verification code");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let Some((code, link)) = link_codes.find(code) else {
+                msg_event.content = str!("Invalid or expired verification code");
+                msg_event.status = 404;
+                msg_events.write(msg_event);
+                continue;
+            };
+
+            let (status, content) = match (event.sender, link.uuid) {
+                /* Requested in Discord, proven by whispering the code in-game */
+                (CmdSender::Minecraft(uuid), None) if event.source.in_game() => {
+                    (200, link_user(&mut settings, uuid, link.discord_id))
+                }
+                /* Requested in-game, proven by running the code as the same Discord user */
+                (CmdSender::Discord(user_id), Some(uuid)) if user_id == link.discord_id => {
+                    (200, link_user(&mut settings, uuid, user_id))
+                }
+                (CmdSender::Discord(_), Some(_)) => {
+                    (403, str!("This code was issued for another Discord account"))
+                }
+                (CmdSender::Discord(_) | CmdSender::Console, None) => {
+                    (406, str!("Whisper this code to any bot in-game"))
+                }
+                (CmdSender::Minecraft(_) | CmdSender::Console, _) => {
+                    (406, str!("Run this code in Discord to confirm"))
+                }
+            };
+
+            if status == 200 {
+                link_codes.remove(&code);
+            }
+
+            msg_event.content = content;
+            msg_event.status = status;
+            msg_events.write(msg_event);
+        }
+    }
+}
+
+fn link_user(settings: &mut ResMut<GlobalSettings>, uuid: Uuid, discord_id: u64) -> String {
+    settings
+        .users
+        .entry(uuid)
+        .and_modify(|user| user.discord_id = discord_id.to_string())
+        .or_insert_with(|| User {
+            discord_id: discord_id.to_string(),
+            ..Default::default()
+        });
+    settings.save().expect("Failed to save settings");
+
+    str!("Successfully linked")
+}
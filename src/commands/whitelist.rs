@@ -1,3 +1,10 @@
+#[cfg(feature = "api")]
+use std::{collections::VecDeque, time::SystemTime};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use azalea::{
     app::{App, Plugin, Update},
     ecs::prelude::*,
@@ -5,8 +12,6 @@ use azalea::{
     player::PlayerInfo,
 };
 #[cfg(feature = "bot")]
-use serde::Deserialize;
-#[cfg(feature = "bot")]
 use serenity::all::UserId;
 use uuid::Uuid;
 
@@ -20,11 +25,15 @@ impl Cmd for WhitelistCommandPlugin {
     fn aliases(&self) -> Vec<&'static str> {
         vec!["whitelist"]
     }
+
+    fn description(&self) -> &'static str {
+        "Add or remove players from the whitelist or link their Discord"
+    }
 }
 
 impl Plugin for WhitelistCommandPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.init_resource::<LinkCodes>().add_systems(
             Update,
             Self::handle_whitelist_cmd_events
                 .ambiguous_with_all()
@@ -34,6 +43,68 @@ impl Plugin for WhitelistCommandPlugin {
     }
 }
 
+/// Characters used in link codes, without ones that are easily confused.
+const LINK_CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const LINK_CODE_LENGTH: usize = 6;
+
+/// Pending account links by one-time verification code.
+#[derive(Clone, Debug, Default, Deref, DerefMut, Resource)]
+pub struct LinkCodes(HashMap<String, LinkCode>);
+
+#[derive(Clone, Debug)]
+pub struct LinkCode {
+    pub discord_id: u64,
+    /// Known when the link was requested in-game and must be confirmed from Discord.
+    pub uuid:       Option<Uuid>,
+    pub expires_at: Instant,
+}
+
+impl LinkCodes {
+    /// Issue a new code, replacing any previous code for the same link request.
+    ///
+    /// # Panics
+    /// Will panic if the OS random number generator fails.
+    pub fn issue(&mut self, discord_id: u64, uuid: Option<Uuid>, expiry: Duration) -> String {
+        let now = Instant::now();
+        self.retain(|_, link| {
+            link.expires_at > now && (link.discord_id != discord_id || link.uuid != uuid)
+        });
+
+        /* Codes grant whitelist access, so they come from the OS random number generator */
+        let code = loop {
+            let mut bytes = [0; LINK_CODE_LENGTH];
+            getrandom::fill(&mut bytes).expect("Failed to get random bytes from the OS");
+
+            /* 32 characters divide 256 evenly, so the modulo isn't biased */
+            let code = bytes
+                .iter()
+                .map(|byte| char::from(LINK_CODE_CHARS[usize::from(*byte) % LINK_CODE_CHARS.len()]))
+                .collect::<String>();
+
+            if !self.contains_key(&code) {
+                break code;
+            }
+        };
+
+        self.insert(code.clone(), LinkCode {
+            discord_id,
+            uuid,
+            expires_at: now + expiry,
+        });
+
+        code
+    }
+
+    /// Find an unexpired code, codes are case-insensitive.
+    #[must_use]
+    pub fn find(&self, code: &str) -> Option<(String, &LinkCode)> {
+        let code = code.to_uppercase();
+        self.get(&code)
+            .filter(|link| link.expires_at > Instant::now())
+            .map(|link| (code, link))
+    }
+}
+
 impl WhitelistCommandPlugin {
     /// # Panics
     /// Will panic if `DeriveTomlConfig::save` fails.
@@ -41,6 +112,7 @@ impl WhitelistCommandPlugin {
         mut cmd_events: MessageReader<CmdEvent>,
         mut msg_events: MessageWriter<MsgEvent>,
         mut settings: ResMut<GlobalSettings>,
+        mut link_codes: ResMut<LinkCodes>,
         tab_list: Res<TabList>,
     ) {
         if let Some(event) = cmd_events.read().next() {
@@ -58,7 +130,7 @@ impl WhitelistCommandPlugin {
             };
 
             let Some(action) = args.pop_front() else {
-                msg_event.content = str!("Missing action | Actions: add, remove, link, & set");
+                msg_event.content = str!("Missing action | Actions: add, remove, link, set, & token");
                 msg_event.status = 404;
                 msg_events.write(msg_event);
                 return;
@@ -68,13 +140,15 @@ impl WhitelistCommandPlugin {
             let (status, content) = match action.as_ref() {
                 "add" => handle_add(&mut settings, discord_id, &tab_list),
                 #[cfg(feature = "bot")]
-                "link" => handle_link(&mut settings, discord_id, &event.sender),
+                "link" => handle_link(&settings, &mut link_codes, discord_id, event),
                 "remove" => handle_remove(&mut settings, discord_id, &tab_list),
                 #[cfg(feature = "api")]
-                "set" => handle_set(&mut settings, discord_id, &event.sender),
+                "set" => handle_set(&mut settings, discord_id, event),
+                #[cfg(feature = "api")]
+                "token" => handle_token(&mut settings, discord_id, args, event),
                 _ => (
                     406,
-                    str!("Invalid action | Actions: add, remove, link, & set"),
+                    str!("Invalid action | Actions: add, remove, link, set, & token"),
                 ),
             };
 
@@ -135,66 +209,36 @@ fn handle_remove(
 
 #[cfg(feature = "bot")]
 fn handle_link(
-    settings: &mut ResMut<GlobalSettings>,
+    settings: &GlobalSettings,
+    link_codes: &mut LinkCodes,
     first_arg: Option<String>,
-    sender: &CmdSender,
+    event: &CmdEvent,
 ) -> (u16, String) {
-    match sender {
-        #[cfg(feature = "api")]
-        CmdSender::ApiServer(uuid) => {
-            let Some(discord_id) = first_arg else {
-                return (404, str!("Missing Discord user id"));
-            };
-
-            let Ok(discord_id) = discord_id.parse::<UserId>() else {
-                return (404, str!("Invalid Discord user id"));
-            };
-
-            settings
-                .users
-                .entry(*uuid)
-                .and_modify(|user| user.discord_id = discord_id.to_string())
-                .or_insert_with(|| User {
-                    discord_id: discord_id.to_string(),
-                    ..Default::default()
-                });
-            settings.save().expect("Failed to save settings");
-
-            (200, str!("Successfully linked discord"))
-        }
+    let prefix = &settings.command_prefix;
+    let minutes = settings.link_code_expiry.as_secs().div_ceil(60);
+    match &event.sender {
+        CmdSender::Console => (406, str!("The console can't link itself, link in-game instead")),
         CmdSender::Discord(discord_id) => {
-            let Some(link_id) = first_arg else {
-                return (404, str!("Missing auth code (Join: auth.aristois.net)"));
-            };
-
-            let path = format!("https://auth.aristois.net/token/{link_id}");
-            let Ok(mut response) = ureq::get(&path).call() else {
-                return (406, str!("Invalid auth code (Join: auth.aristois.net)"));
-            };
-
-            let code = response.status();
-            let Ok(json) = response.body_mut().read_json::<Json>() else {
-                return (500, str!("Failed to parse JSON"));
-            };
-
-            let Some(uuid) = json.uuid else {
+            /* Anyone in the channel could whisper the code before its owner does */
+            if event
+                .source
+                .downcast::<DiscordFrontend>()
+                .is_some_and(|frontend| frontend.interaction.is_none())
+            {
                 return (
-                    code.as_u16(),
-                    format!("Authentication {}: {}", json.status, json.message),
+                    406,
+                    format!("Run /whitelist link or DM me {prefix}whitelist link instead"),
                 );
-            };
+            }
 
-            settings
-                .users
-                .entry(uuid)
-                .and_modify(|user| user.discord_id = discord_id.to_string())
-                .or_insert_with(|| User {
-                    discord_id: discord_id.to_string(),
-                    ..Default::default()
-                });
-            settings.save().expect("Failed to save settings");
+            let code = link_codes.issue(*discord_id, None, settings.link_code_expiry);
 
-            (200, str!("Successfully linked"))
+            (
+                200,
+                format!(
+                    "Whisper `{prefix}verify {code}` to any bot in-game within {minutes} minutes to link your account"
+                ),
+            )
         }
         CmdSender::Minecraft(uuid) => {
             let Some(discord_id) = first_arg else {
@@ -205,17 +249,13 @@ fn handle_link(
                 return (404, str!("Invalid Discord user id"));
             };
 
-            settings
-                .users
-                .entry(*uuid)
-                .and_modify(|user| user.discord_id = discord_id.to_string())
-                .or_insert_with(|| User {
-                    discord_id: discord_id.to_string(),
-                    ..Default::default()
-                });
-            settings.save().expect("Failed to save settings");
+            /* Anyone can whisper any id, so the Discord side has to confirm it */
+            let code = link_codes.issue(discord_id.get(), Some(*uuid), settings.link_code_expiry);
 
-            (200, str!("Successfully linked"))
+            (
+                200,
+                format!("Run {prefix}verify {code} in Discord within {minutes} minutes to confirm"),
+            )
         }
     }
 }
@@ -224,41 +264,133 @@ fn handle_link(
 fn handle_set(
     settings: &mut ResMut<GlobalSettings>,
     api_password: Option<String>,
-    sender: &CmdSender,
+    event: &CmdEvent,
 ) -> (u16, String) {
-    match sender {
-        CmdSender::ApiServer(_) => (500, str!("You can't update your API password on the API")),
-        #[cfg(feature = "bot")]
-        CmdSender::Discord(_) => (500, str!("You can't update your API password on Discord")),
-        CmdSender::Minecraft(uuid) => {
-            let Some(api_password) = api_password else {
-                return (404, str!("Missing Discord user id"));
+    if !event.source.in_game() {
+        let name = event.source.name();
+        return (500, format!("You can't update your API password on {name}"));
+    }
+
+    let CmdSender::Minecraft(uuid) = event.sender else {
+        return (500, str!("You can only update your API password in-game"));
+    };
+
+    let Some(api_password) = api_password else {
+        return (404, str!("Missing API password"));
+    };
+
+    let Ok(api_password) = hash_secret(&api_password) else {
+        return (500, str!("Failed to hash password"));
+    };
+
+    settings
+        .users
+        .entry(uuid)
+        .and_modify(|user| user.api_password.clone_from(&api_password))
+        .or_insert_with(|| User {
+            api_password,
+            ..Default::default()
+        });
+    settings.save().expect("Failed to save settings");
+
+    (200, str!("Successfully updated password"))
+}
+
+#[cfg(feature = "api")]
+fn handle_token(
+    settings: &mut ResMut<GlobalSettings>,
+    action: Option<String>,
+    mut args: VecDeque<String>,
+    event: &CmdEvent,
+) -> (u16, String) {
+    let CmdSender::Minecraft(uuid) = event.sender else {
+        return (500, str!("You can only manage API tokens as a Minecraft player"));
+    };
+
+    match action.as_deref() {
+        Some("create") => {
+            if !event.source.in_game() {
+                let name = event.source.name();
+                return (500, format!("You can't create API tokens on {name}"));
+            }
+
+            let Some(name) = args.pop_front() else {
+                return (404, str!("Missing token name"));
             };
 
-            settings
-                .users
-                .entry(*uuid)
-                .and_modify(|user| user.api_password.clone_from(&api_password))
-                .or_insert_with(|| User {
-                    api_password,
-                    ..Default::default()
-                });
+            let scope = match args.pop_front() {
+                None => ApiScope::Full,
+                Some(scope) => match ApiScope::from_name(&scope) {
+                    Some(scope) => scope,
+                    None => return (406, str!("Invalid scope | Scopes: full, pearl, & read")),
+                },
+            };
+
+            if settings
+                .api_tokens
+                .iter()
+                .any(|token| token.owner == uuid && token.name == name)
+            {
+                return (409, format!("Token already exists: {name}"));
+            }
+
+            let id = random_hex(4);
+            let secret = random_hex(24);
+            let hash = hash_token_secret(&secret);
+
+            let created = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+
+            settings.api_tokens.push(ApiToken {
+                id: id.clone(),
+                name: name.clone(),
+                owner: uuid,
+                scope,
+                hash,
+                created,
+            });
             settings.save().expect("Failed to save settings");
 
-            (200, str!("Successfully updated password"))
+            let scope = scope.name();
+            (200, format!("Created {scope} token {name}: sbt_{id}_{secret}"))
+        }
+        Some("list") => {
+            let tokens = settings
+                .api_tokens
+                .iter()
+                .filter(|token| token.owner == uuid)
+                .map(|token| format!("{} ({})", token.name, token.scope.name()))
+                .collect::<Vec<_>>();
+
+            if tokens.is_empty() {
+                (404, str!("No API tokens"))
+            } else {
+                (200, format!("API tokens: {}", tokens.join(", ")))
+            }
+        }
+        Some("revoke") => {
+            let Some(name) = args.pop_front() else {
+                return (404, str!("Missing token name"));
+            };
+
+            let count = settings.api_tokens.len();
+            settings
+                .api_tokens
+                .retain(|token| token.owner != uuid || token.name != name);
+
+            if settings.api_tokens.len() == count {
+                return (404, format!("Token not found: {name}"));
+            }
+
+            settings.save().expect("Failed to save settings");
+            (200, format!("Revoked token: {name}"))
         }
+        _ => (406, str!("Invalid token action | Actions: create, list, & revoke")),
     }
 }
 
 fn try_find_player<'a>(tab_list: &'a TabList, name: &str) -> Option<(&'a Uuid, &'a PlayerInfo)> {
     tab_list.iter().find(|(_, info)| info.profile.name == name)
 }
-
-#[cfg(feature = "bot")]
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct Json {
-    message: String,
-    status:  String,
-    uuid:    Option<Uuid>,
-}
@@ -22,7 +22,11 @@ pub mod parsers;
 pub mod settings;
 pub mod trackers;
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use anyhow::{bail, Result};
 use azalea::{
@@ -96,6 +100,7 @@ pub async fn start() -> Result<()> {
             DefaultBotPlugins,
             DefaultSwarmPlugins,
             CommandsPluginGroup,
+            ConsoleParserPlugin,
             MinecraftParserPlugin,
             ModulesPluginGroup,
             SettingsPluginGroup,
@@ -110,6 +115,11 @@ pub async fn start() -> Result<()> {
         info!("{link}");
     }
 
+    #[cfg(unix)]
+    if global_settings.control_socket {
+        client = client.add_plugins(ControlParserPlugin);
+    }
+
     #[cfg(feature = "api")]
     if global_settings.http_api.enabled {
         client = client.add_plugins(HttpApiParserPlugin);
@@ -117,13 +127,16 @@ pub async fn start() -> Result<()> {
 
     #[cfg(feature = "bot")]
     if !global_settings.discord_token.is_empty() {
-        let gateway_intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+        let gateway_intents = GatewayIntents::GUILD_MESSAGES
+            | GatewayIntents::DIRECT_MESSAGES
+            | GatewayIntents::MESSAGE_CONTENT;
         let configuration = DiscordBotConfig::default()
             .gateway_intents(gateway_intents)
             .token(global_settings.discord_token.clone());
 
         client = client.add_plugins((
             DiscordBotPlugin::new(configuration),
+            DiscordBridgePlugin,
             DiscordParserPlugin,
         ));
     }
@@ -147,7 +160,7 @@ pub async fn start() -> Result<()> {
 
 #[derive(Clone, Component, Resource, SmartDefault)]
 pub struct SwarmState {
-    auto_reconnect: Arc<RwLock<HashMap<String, (bool, u64)>>>,
+    auto_reconnect: Arc<RwLock<HashMap<String, ReconnectRecord>>>,
 }
 
 /// # Errors
@@ -165,21 +178,29 @@ pub async fn swarm_handler(swarm: Swarm, event: SwarmEvent, state: SwarmState) -
         }
         SwarmEvent::Disconnect(ref account, ref join_opts) => loop {
             let bot_name = account.username().to_lowercase();
-            let Some((rejoin, secs)) = state.auto_reconnect.read().get(&bot_name).copied() else {
+            let Some(record) = state.auto_reconnect.read().get(&bot_name).cloned() else {
                 state
                     .auto_reconnect
                     .write()
-                    .insert(bot_name.to_lowercase(), (false, 5));
+                    .insert(bot_name.to_lowercase(), ReconnectRecord::default());
 
                 continue; /* AutoReconnect: Missing */
             };
 
-            tokio::time::sleep(Duration::from_secs(secs)).await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
 
-            if !rejoin {
+            if !LocalSettings::new(account.username())?.file_path().exists() {
+                break; /* Account Removed */
+            }
+
+            if !record.enabled {
                 continue; /* AutoReconnect: Disabled */
             }
 
+            if record.resume_at.is_some_and(|resume_at| resume_at > SystemTime::now()) {
+                continue; /* AutoReconnect: Waiting */
+            }
+
             info!("AutoReconnecting on {}", account.username());
             swarm.add_with_opts(account, state.clone(), join_opts).await;
 
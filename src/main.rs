@@ -7,5 +7,10 @@ async fn main() -> anyhow::Result<()> {
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
         .init();
 
+    #[cfg(unix)]
+    if std::env::args().nth(1).as_deref() == Some("ctl") {
+        return shaysbot::prelude::ControlParserPlugin::client(std::env::args().skip(2).collect());
+    }
+
     shaysbot::start().await
 }
@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime};
+
 use azalea::{
     app::{App, Plugin, Update},
     auto_reconnect::start_rejoin_on_disconnect,
@@ -6,18 +8,35 @@ use azalea::{
     ecs::prelude::*,
     events::disconnect_listener,
     local_player::TabList,
-    packet::game::{GamePingEvent, ReceiveGamePacketEvent, SendGamePacketEvent},
+    packet::{
+        game::{GamePingEvent, ReceiveGamePacketEvent, SendGamePacketEvent},
+        login::ReceiveLoginPacketEvent,
+    },
     player::GameProfileComponent,
-    protocol::packets::game::{ClientboundGamePacket, ServerboundPong},
+    protocol::packets::{
+        game::{ClientboundGamePacket, ServerboundPong},
+        login::ClientboundLoginPacket,
+    },
     registry::builtin::EntityKind,
     FormattedText,
 };
+use chrono::{Local, NaiveTime};
 use itertools::Itertools;
 
 use crate::prelude::*;
 
 pub const ZENITH_PREFIX: &str = "[AutoDisconnect] ";
 
+/// Reconnect state and attempts for an account, exposed through the status command.
+#[derive(Clone, Debug, Default)]
+pub struct ReconnectRecord {
+    pub attempts:     u32,
+    pub connected_at: Option<SystemTime>,
+    pub enabled:      bool,
+    pub reason:       String,
+    pub resume_at:    Option<SystemTime>,
+}
+
 /// Automatically leave the server when in danger
 pub struct AutoLeavePlugin;
 
@@ -30,6 +49,7 @@ impl Plugin for AutoLeavePlugin {
                 (
                     Self::handle_add_entity_packets,
                     Self::handle_disconnect_events,
+                    Self::handle_login_packets,
                     Self::handle_transfer_packets,
                     Self::handle_requeue,
                 )
@@ -44,44 +64,133 @@ impl Plugin for AutoLeavePlugin {
 pub struct GrimDisconnect;
 
 impl AutoLeavePlugin {
-    #[allow(clippy::cognitive_complexity)]
     pub fn handle_disconnect_events(
         mut events: MessageReader<DisconnectEvent>,
-        mut query: Query<&GameProfileComponent>,
+        mut query: Query<(&GameProfileComponent, Has<ZenithLeave>)>,
         mut commands: Commands,
+        mut metrics: ResMut<Metrics>,
+        global_settings: Res<GlobalSettings>,
         swarm_state: Res<SwarmState>,
     ) {
         for event in events.read() {
-            let Ok(game_profile) = query.get_mut(event.entity) else {
-                continue;
-            };
-
-            let Some(reason) = &event.reason else {
+            let Ok((game_profile, zenith_leave)) = query.get_mut(event.entity) else {
                 continue;
             };
 
             let username = &game_profile.name;
+            let mut reason = event
+                .reason
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default();
             info!("[{username}] Disconnect Reason: {reason}");
+            if zenith_leave {
+                reason = format!("{LEAVE_PREFIX}{reason}"); /* Left through ZenithProxy */
+            }
+
+            let settings = &global_settings.reconnect;
+            let mut auto_reconnect = swarm_state.auto_reconnect.write();
+            let record = auto_reconnect.entry(username.to_lowercase()).or_default();
+            record.reason.clone_from(&reason);
+
+            let rule = settings
+                .rules
+                .iter()
+                .find(|rule| rule.pattern.is_match(&reason));
 
-            let auto_reconnect = if str!(reason).starts_with(ZENITH_PREFIX) {
-                info!("[{username}] AutoReconnect Disabled: ZenithProxy AutoDisconnect");
-                (true, 10)
-            } else if str!(reason).starts_with(LEAVE_PREFIX) {
-                info!("[{username}] AutoReconnect Disabled: Leave Command");
-                (false, 10)
-            } else if str!(reason).starts_with("Connection throttled") {
-                info!("[{username}] AutoReconnected too fast, waiting 30s...");
-                (true, 30)
-            } else {
-                info!("[{username}] AutoReconnecting in 10s...");
-                (true, 10)
+            let delay = match rule.map(|rule| &rule.action) {
+                Some(ReconnectAction::Reconnect { delay }) => Some(*delay),
+                Some(ReconnectAction::Backoff { base, max, jitter }) => {
+                    let exponent = record.attempts.min(16);
+                    let delay = base.saturating_mul(2_u32.pow(exponent)).min(*max);
+                    let jitter = if jitter.is_finite() {
+                        jitter.clamp(0.0, 1.0) * fastrand::f64().mul_add(2.0, -1.0)
+                    } else {
+                        0.0 /* Invalid Jitter */
+                    };
+
+                    Some(delay.mul_f64(1.0 + jitter))
+                }
+                Some(ReconnectAction::StopUntil { until }) => {
+                    metrics.reconnect(username, "stop");
+                    match NaiveTime::parse_from_str(until, "%H:%M") {
+                        Ok(time) => {
+                            let now = Local::now().time();
+                            let secs = (time - now).num_seconds().rem_euclid(86_400);
+                            record.enabled = true;
+                            record.resume_at =
+                                Some(SystemTime::now() + Duration::from_secs(secs.unsigned_abs()));
+                            info!("[{username}] AutoReconnect Paused: Until {until}");
+                        }
+                        Err(error) => {
+                            warn!("[{username}] Invalid reconnect time {until}: {error}");
+                            record.enabled = false;
+                        }
+                    }
+
+                    None
+                }
+                Some(ReconnectAction::Stop) | None => {
+                    metrics.reconnect(username, "stop");
+                    info!("[{username}] AutoReconnect Disabled: {reason}");
+                    record.enabled = false;
+
+                    None
+                }
             };
 
-            commands.entity(event.entity).remove::<GrimDisconnect>();
-            swarm_state
-                .auto_reconnect
-                .write()
-                .insert(username.to_lowercase(), auto_reconnect);
+            if let Some(delay) = delay {
+                record.attempts += 1;
+                if settings.max_attempts > 0 && record.attempts > settings.max_attempts {
+                    let attempts = settings.max_attempts;
+                    info!("[{username}] AutoReconnect Disabled: Gave up after {attempts} attempts");
+                    metrics.reconnect(username, "gave_up");
+                    record.enabled = false;
+                } else {
+                    if let Some(rule) = rule {
+                        let name = if rule.name.is_empty() {
+                            "unnamed"
+                        } else {
+                            &rule.name
+                        };
+                        metrics.reconnect(username, name);
+                    }
+
+                    let secs = delay.as_secs();
+                    info!(
+                        "[{username}] AutoReconnecting in {secs}s... (Attempt {})",
+                        record.attempts
+                    );
+                    record.enabled = true;
+                    record.resume_at = Some(SystemTime::now() + delay);
+                }
+            }
+
+            commands
+                .entity(event.entity)
+                .remove::<(GrimDisconnect, ZenithLeave)>();
+        }
+    }
+
+    pub fn handle_login_packets(
+        mut packet_events: MessageReader<ReceiveLoginPacketEvent>,
+        query: Query<&GameProfileComponent>,
+        swarm_state: Res<SwarmState>,
+    ) {
+        for event in packet_events.read() {
+            let ClientboundLoginPacket::LoginFinished(_) = event.packet.as_ref() else {
+                continue;
+            };
+
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            let mut auto_reconnect = swarm_state.auto_reconnect.write();
+            let username = game_profile.name.to_lowercase();
+            let record = auto_reconnect.entry(username).or_default();
+            record.attempts = 0; /* Successful Login */
+            record.connected_at = Some(SystemTime::now());
         }
     }
 
@@ -169,13 +278,16 @@ impl AutoLeavePlugin {
     pub fn handle_requeue(
         query: Query<(Entity, &GameTicks, &LocalSettings, &TabList), With<RawConnection>>,
         mut disconnect_events: MessageWriter<DisconnectEvent>,
+        mut zenith_events: MessageWriter<ZenithEvent>,
         mut commands: Commands,
     ) {
         query
             .iter()
             .filter(|(_, _, _, tab_list)| tab_list.len() > 1)
-            .sorted_by_key(|(_, ticks, _, _)| ticks.0)
-            .chunk_by(|(_, _, settings, _)| &settings.auto_pearl.location)
+            .sorted_by_key(|(_, ticks, settings, _)| {
+                (&settings.server, &settings.auto_pearl.location, ticks.0)
+            })
+            .chunk_by(|(_, _, settings, _)| (&settings.server, &settings.auto_pearl.location))
             .into_iter()
             .for_each(|(_, group)| {
                 for (i, (entity, _, settings, _)) in group.enumerate() {
@@ -187,7 +299,12 @@ impl AutoLeavePlugin {
                         continue;
                     }
 
-                    if settings.auto_leave.grim_disconnect {
+                    if settings.zenith_proxy.enabled {
+                        zenith_events.write(ZenithEvent {
+                            entity,
+                            action: ZenithAction::Disconnect,
+                        });
+                    } else if settings.auto_leave.grim_disconnect {
                         commands.entity(entity).insert(GrimDisconnect);
                     } else {
                         disconnect_events.write(DisconnectEvent {
@@ -64,8 +64,36 @@ pub struct PearlPullEvent(pub PearlEvent);
 
 #[derive(Default, Resource)]
 pub struct ResendPearlEvents {
-    goto: Vec<PearlGotoEvent>,
-    pull: Vec<PearlPullEvent>,
+    goto:   Vec<PearlGotoEvent>,
+    pull:   Vec<PearlPullEvent>,
+    /// Owner of the chamber each bot is currently walking to.
+    active: HashMap<Entity, Uuid>,
+}
+
+impl ResendPearlEvents {
+    /// Check if a bot is currently walking to an owner's chamber.
+    #[must_use]
+    pub fn is_active(&self, entity: Entity, owner_uuid: Uuid) -> bool {
+        self.active.get(&entity) == Some(&owner_uuid)
+    }
+
+    /// Remove a bot's waiting pearl events for an owner, returns whether any were removed.
+    pub fn cancel(&mut self, entity: Entity, owner_uuid: Uuid) -> bool {
+        let was_active = self.is_active(entity, owner_uuid);
+        if was_active {
+            self.active.remove(&entity);
+        }
+
+        let is_other = |event: &PearlEvent| {
+            event.entity != entity || event.owner_uuid != owner_uuid
+        };
+
+        let len = self.goto.len() + self.pull.len();
+        self.goto.retain(|event| is_other(&event.0));
+        self.pull.retain(|event| is_other(&event.0));
+
+        was_active || len != self.goto.len() + self.pull.len()
+    }
 }
 
 impl AutoPearlPlugin {
@@ -110,6 +138,9 @@ impl AutoPearlPlugin {
             }
 
             let pos = event.block_pos.to_vec3_floored();
+            pearl_pending_events
+                .active
+                .insert(event.entity, event.owner_uuid);
             goto_events.write(GotoEvent::new(
                 event.entity,
                 RadiusGoal { radius: 3.0, pos },
@@ -123,6 +154,7 @@ impl AutoPearlPlugin {
     }
 
     pub fn handle_pull_pearl_events(
+        mut chamber_events: MessageWriter<ChamberEvent>,
         mut goto_events: MessageWriter<GotoEvent>,
         mut pearl_pending_events: ResMut<ResendPearlEvents>,
         mut pearl_pull_events: MessageReader<PearlPullEvent>,
@@ -165,6 +197,15 @@ impl AutoPearlPlugin {
                 packet,
             });
 
+            pearl_pending_events.active.remove(&event.entity);
+
+            chamber_events.write(ChamberEvent {
+                entity:     event.entity,
+                owner_uuid: event.owner_uuid,
+                block_pos:  event.block_pos,
+                kind:       ChamberEventKind::Pulled,
+            });
+
             let goal = RadiusGoal {
                 pos:    event.idle_goal.coords,
                 radius: event.idle_goal.radius + 1.0,
@@ -0,0 +1,305 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    client_chat::{ChatKind, ChatReceivedEvent},
+    ecs::prelude::*,
+    FormattedText,
+};
+use bevy_discord::{messages::bot::DiscordMessage, res::DiscordHttpResource, DiscordSystems};
+use ncr::AesKey;
+use serenity::{all::ChannelId, json::json};
+
+use crate::prelude::*;
+
+/// Relay chat between each bot and its Discord channel
+pub struct DiscordBridgePlugin;
+
+impl Plugin for DiscordBridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                Self::handle_chat_received_events,
+                Self::handle_discord_message_events
+                    .after(DiscordSystems)
+                    .before(MinecraftParserPlugin::handle_chat_outbox),
+            ),
+        );
+    }
+}
+
+impl DiscordBridgePlugin {
+    pub fn handle_chat_received_events(
+        mut chat_received_events: MessageReader<ChatReceivedEvent>,
+        query: Query<(Entity, &LocalSettings)>,
+        settings: Res<GlobalSettings>,
+        discord: Option<Res<DiscordHttpResource>>,
+    ) {
+        let Some(discord) = discord else {
+            return;
+        };
+
+        for event in chat_received_events.read() {
+            let Ok((_, local_settings)) = query.get(event.entity) else {
+                continue;
+            };
+
+            let bridge = &local_settings.discord_bridge;
+            let channel_id = local_settings.discord_channel;
+            if !bridge.enabled || channel_id == ChannelId::default() {
+                continue; /* Bridge Disabled */
+            }
+
+            let bots = query
+                .iter()
+                .filter(|(_, bot)| is_bridged(bot, channel_id))
+                .map(|(entity, bot)| (entity, bot.server.as_str()));
+            if !is_bridge_leader(event.entity, &local_settings.server, bots) {
+                continue; /* Relayed By Another Bot */
+            }
+
+            /* Servers with custom formats send whispers as system messages */
+            let profile = settings.server_profile(&local_settings.server);
+            let chat_profile = settings.chat_profile(&profile.chat_profile);
+            let (username, content) = event.packet.split_sender_and_content();
+            let (whisper, body) = if username.is_some() {
+                (event.packet.is_whisper(), content.clone())
+            } else if let Some((_, body, whisper)) = parse_chat_profile(&chat_profile, &content) {
+                (whisper, body)
+            } else {
+                (false, content.clone())
+            };
+
+            let message = event.packet.message();
+            let kind = ChatCategory::from(&message);
+            if (kind == ChatCategory::Join && !bridge.joins)
+                || (kind == ChatCategory::Death && !bridge.deaths)
+                || (whisper && !bridge.whispers)
+            {
+                continue; /* Filtered */
+            }
+
+            /* Show decrypted No Chat Reports messages where the key allows */
+            let key = AesKey::decode_base64(&settings.chat.key).unwrap_or_else(|_| KEY.clone());
+            let (_, plaintext) = find_encryption(&body, &key);
+            if plaintext.starts_with(&settings.command_prefix) {
+                continue; /* Commands may contain passwords and verification codes */
+            }
+
+            let content = match (username, find_encryption(&content, &key)) {
+                (Some(username), (Some(_encryption), plaintext)) => format!(
+                    "**{}** 🔒 {}",
+                    escape_markdown(&username),
+                    escape_markdown(&plaintext)
+                ),
+                _ => to_markdown(&message),
+            };
+
+            if content.trim().is_empty() {
+                continue;
+            }
+
+            let client = discord.client();
+            let chunks = split_message(&content, DISCORD_MAX_LENGTH);
+            tokio::task::spawn_local(async move {
+                for content in chunks {
+                    let map = &json!({
+                        "content": content,
+                        "allowed_mentions": { "parse": [] },
+                    });
+
+                    if let Err(error) = client.send_message(channel_id, Vec::new(), map).await {
+                        error!("{error}");
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    pub fn handle_discord_message_events(
+        mut message_events: MessageReader<DiscordMessage>,
+        mut query: Query<(Entity, &LocalSettings, &mut ChatOutbox)>,
+        settings: Res<GlobalSettings>,
+    ) {
+        for event in message_events.read() {
+            let message = &event.new_message;
+            if message.author.bot || message.content.starts_with(&settings.command_prefix) {
+                continue; /* Bots and Commands */
+            }
+
+            let user_id = str!(message.author.id);
+            if !settings
+                .users
+                .values()
+                .any(|user| user.discord_id == user_id)
+            {
+                continue; /* Not Linked */
+            }
+
+            let author = message
+                .author
+                .global_name
+                .as_ref()
+                .unwrap_or(&message.author.name);
+            let content = sanitize_chat(&message.content);
+            if content.is_empty() {
+                continue;
+            }
+
+            let bots = query
+                .iter()
+                .filter(|(_, bot, _)| is_bridged(bot, message.channel_id))
+                .map(|(entity, bot, _)| (entity, bot.server.clone()))
+                .collect::<Vec<_>>();
+
+            for (entity, local_settings, mut chat_outbox) in &mut query {
+                if !is_bridged(local_settings, message.channel_id) {
+                    continue;
+                }
+
+                let bots = bots
+                    .iter()
+                    .map(|(entity, server)| (*entity, server.as_str()));
+                if !is_bridge_leader(entity, &local_settings.server, bots) {
+                    continue; /* Relayed By Another Bot */
+                }
+
+                let profile = settings.server_profile(&local_settings.server);
+                let chat_profile = settings.chat_profile(&profile.chat_profile);
+                let prefix = format!("[{}] ", sanitize_chat(author));
+                let limit = chat_profile
+                    .max_length
+                    .saturating_sub(prefix.chars().count());
+
+                for chunk in split_message(&content, limit) {
+                    chat_outbox.push(
+                        OutgoingChat {
+                            content:  format!("{prefix}{chunk}"),
+                            key:      format!("{prefix}{chunk}"),
+                            kind:     ChatKind::Message,
+                            priority: ChatPriority::Reply,
+                        },
+                        &local_settings.anti_spam,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Whether a bot relays chat between its server and the Discord channel.
+fn is_bridged(local_settings: &LocalSettings, channel_id: ChannelId) -> bool {
+    local_settings.discord_bridge.enabled && local_settings.discord_channel == channel_id
+}
+
+/// Bots on the same server and channel elect the lowest entity to relay, so chat isn't duplicated.
+fn is_bridge_leader<'a>(
+    entity: Entity,
+    server: &str,
+    bots: impl IntoIterator<Item = (Entity, &'a str)>,
+) -> bool {
+    bots.into_iter()
+        .filter(|(_, bot_server)| *bot_server == server)
+        .map(|(entity, _)| entity)
+        .min()
+        == Some(entity)
+}
+
+/// Categories of in-game chat the bridge can filter.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChatCategory {
+    Chat,
+    Death,
+    Join,
+}
+
+impl From<&FormattedText> for ChatCategory {
+    fn from(message: &FormattedText) -> Self {
+        if let FormattedText::Translatable(component) = message {
+            if component.key.starts_with("death.") {
+                return Self::Death;
+            }
+
+            if component.key.starts_with("multiplayer.player.") {
+                return Self::Join;
+            }
+        }
+
+        /* Servers that send plain text join messages */
+        if regex_is_match!(
+            r"^[a-zA-Z_0-9]{1,16} (?:joined|left) the game$",
+            &message.to_string()
+        ) {
+            return Self::Join;
+        }
+
+        Self::Chat
+    }
+}
+
+/// Convert formatted chat into Discord markdown, escaping any markdown in the text.
+#[must_use]
+pub fn to_markdown(message: &FormattedText) -> String {
+    message
+        .clone()
+        .into_iter()
+        .map(|component| {
+            let style = &component.get_base().style;
+            let text = match &component {
+                FormattedText::Text(text) => text.text.clone(),
+                FormattedText::Translatable(translatable) => translatable
+                    .read()
+                    .map_or_else(|_| translatable.key.clone(), |text| text.to_string()),
+            };
+
+            let mut text = escape_markdown(&text);
+            if text.trim().is_empty() {
+                return text;
+            }
+
+            for (enabled, marker) in [
+                (style.bold, "**"),
+                (style.italic, "*"),
+                (style.underlined, "__"),
+                (style.strikethrough, "~~"),
+                (style.obfuscated, "||"),
+            ] {
+                if enabled == Some(true) {
+                    text = format!("{marker}{text}{marker}");
+                }
+            }
+
+            text
+        })
+        .collect()
+}
+
+/// Escape Discord markdown and break mentions so relayed chat can't ping anyone.
+#[must_use]
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        if matches!(char, '*' | '_' | '~' | '`' | '|' | '>' | '\\' | '[' | ']') {
+            escaped.push('\\');
+        }
+
+        escaped.push(char);
+        if char == '@' {
+            escaped.push('\u{200B}'); /* Zero Width Space */
+        }
+    }
+
+    escaped
+}
+
+/// Remove characters Minecraft rejects in chat, such as section signs and newlines.
+#[must_use]
+pub fn sanitize_chat(text: &str) -> String {
+    text.chars()
+        .map(|char| if char.is_control() { ' ' } else { char })
+        .filter(|&char| char != '§')
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,6 +1,9 @@
 use std::{
     collections::HashMap,
-    sync::{atomic::AtomicUsize, Arc},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use azalea::{
@@ -39,9 +42,12 @@ enum EventType {
     PearlReturn,
     AutoWhitelistAdd,
     PlayerChat,
+    QueueThreshold,
+    QueueConnected,
     ServerDisconnect,
     ServerReconnect,
     ServerError,
+    ApiAlert,
 }
 
 /// A client that sends webhook messages with round-robin URL selection.
@@ -53,6 +59,8 @@ pub struct WebhookClient {
     global_urls:  Arc<Vec<String>>,
     /// Round-robin index for the global URL list.
     global_index: Arc<AtomicUsize>,
+    /// Number of webhook messages that failed to send.
+    failures:     Arc<AtomicU64>,
 }
 
 #[derive(Clone)]
@@ -69,9 +77,15 @@ impl WebhookClient {
             configs:      Arc::new(configs),
             global_urls:  Arc::new(global_urls),
             global_index: Arc::new(AtomicUsize::new(0)),
+            failures:     Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Get the number of webhook messages that failed to send.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
     /// Get the URLs to use for a given event type, falling back to global URLs.
     fn get_urls(&self, event_type: EventType) -> Option<Arc<Vec<String>>> {
         let config = self.configs.get(&event_type)?;
@@ -122,18 +136,23 @@ impl WebhookClient {
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         let url = urls[idx % urls.len()].clone();
         let content = content.to_string();
+        let failures = Arc::clone(&self.failures);
 
         tokio::task::spawn_local({
             async move {
                 if let Err(e) = Self::post_webhook(&url, &content) {
                     error!("Failed to send webhook: {e}");
+                    failures.fetch_add(1, Ordering::Relaxed);
                 }
             }
         });
     }
 
     fn post_webhook(url: &str, content: &str) -> anyhow::Result<()> {
-        let payload = WebhookPayload { content };
+        let payload = WebhookPayload {
+            content,
+            allowed_mentions: AllowedMentions { parse: [] },
+        };
         ureq::post(url).send_json(json!(payload))?;
 
         Ok(())
@@ -185,9 +204,16 @@ impl Plugin for LoggerPlugin {
         add_event!(PearlReturn, event_types.pearl_return, None);
         add_event!(AutoWhitelistAdd, event_types.auto_whitelist_add, None);
         add_event!(PlayerChat, event_types.player_chat, None);
+        add_event!(QueueThreshold, event_types.queue_threshold, None);
+        add_event!(QueueConnected, event_types.queue_connected, None);
         add_event!(ServerDisconnect, event_types.server_disconnect, None);
         add_event!(ServerReconnect, event_types.server_reconnect, None);
         add_event!(ServerError, event_types.server_error, None);
+        add_event!(ApiAlert, event_types.api_alert, None);
+
+        #[cfg(feature = "api")]
+        app.add_message::<ApiAlertEvent>()
+            .add_systems(Update, Self::handle_api_alert_events);
 
         app.insert_resource(WebhookClient::new(event_configs, config.webhooks.clone()))
             .add_systems(
@@ -207,6 +233,7 @@ impl Plugin for LoggerPlugin {
                     Self::handle_pearl_return_events,
                     Self::handle_player_info_remove_packets,
                     Self::handle_player_info_update_packets,
+                    Self::handle_queue_events,
                     Self::handle_remove_entities_packets,
                     Self::handle_server_error_events,
                     Self::handle_server_reconnect_events,
@@ -352,21 +379,22 @@ impl LoggerPlugin {
             };
 
             let username = game_profile.name.clone();
-            let sender = match &event.sender {
-                CmdSender::Minecraft(_) => "console".to_string(),
-                #[cfg(feature = "bot")]
-                CmdSender::Discord(user_id) => format!("discord:{user_id}"),
-                #[cfg(feature = "api")]
-                CmdSender::ApiServer(_) => "api".to_string(),
-            };
+            let sender = format!("{}:{}", event.source.name(), event.sender);
 
             let cmd_name = match event.cmd {
+                Cmds::Account(_) => "/account",
+                Cmds::Config(_) => "/config",
                 Cmds::Interact(_) => "/interact",
                 Cmds::Join(_) => "/join",
                 Cmds::Leave(_) => "/leave",
+                Cmds::Module(_) => "/module",
+                Cmds::Notify(_) => "/notify",
                 Cmds::Pearl(_) => "/pearl",
                 Cmds::Playtime(_) => "/playtime",
+                Cmds::Queue(_) => "/queue",
                 Cmds::Seen(_) => "/seen",
+                Cmds::Status(_) => "/status",
+                Cmds::Verify(_) => "/verify",
                 Cmds::Whitelist(_) => "/whitelist",
             };
 
@@ -447,6 +475,7 @@ impl LoggerPlugin {
                 .0
                 .values()
                 .filter(|c| c.owner_uuid == event.0.owner_uuid)
+                .filter(|c| c.server == local_settings.server)
                 .count();
 
             let content = if count <= local_settings.auto_pearl.pearl_limit {
@@ -537,6 +566,35 @@ impl LoggerPlugin {
         }
     }
 
+    fn handle_queue_events(
+        mut queue_events: MessageReader<QueueEvent>,
+        query: Query<&GameProfileComponent>,
+        webhook: Option<Res<WebhookClient>>,
+    ) {
+        let Some(webhook) = webhook else {
+            return;
+        };
+
+        for event in queue_events.read() {
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            let username = &game_profile.name;
+            match &event.kind {
+                QueueEventKind::Position(_) => {}
+                QueueEventKind::Threshold(threshold, status) => {
+                    let content = format!("[{username}] passed {threshold} in queue, {status}");
+                    webhook.send(EventType::QueueThreshold, content);
+                }
+                QueueEventKind::Connected => {
+                    let content = format!("[{username}] left the queue and reached the server.");
+                    webhook.send(EventType::QueueConnected, content);
+                }
+            }
+        }
+    }
+
     fn handle_remove_entities_packets(
         mut packet_events: MessageReader<ReceiveGamePacketEvent>,
         query: Query<(&PlayerProfiles, &GameProfileComponent)>,
@@ -760,9 +818,36 @@ impl LoggerPlugin {
             }
         }
     }
+
+    #[cfg(feature = "api")]
+    fn handle_api_alert_events(
+        mut alert_events: MessageReader<ApiAlertEvent>,
+        webhook: Option<Res<WebhookClient>>,
+    ) {
+        let Some(webhook) = webhook else {
+            return;
+        };
+
+        for event in alert_events.read() {
+            let content = format!(
+                "[api] {} locked out for {}s after {} failed logins",
+                event.key,
+                event.lockout.as_secs(),
+                event.failures
+            );
+            webhook.send(EventType::ApiAlert, content);
+        }
+    }
 }
 
 #[derive(serde::Serialize)]
 struct WebhookPayload<'a> {
-    content: &'a str,
+    content:          &'a str,
+    /// Player names and chat are untrusted, never let them ping anyone.
+    allowed_mentions: AllowedMentions,
+}
+
+#[derive(serde::Serialize)]
+struct AllowedMentions {
+    parse: [&'static str; 0],
 }
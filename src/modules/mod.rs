@@ -6,7 +6,10 @@ pub mod auto_look;
 pub mod auto_pearl;
 pub mod auto_totem;
 pub mod auto_whitelist;
+#[cfg(feature = "bot")]
+pub mod discord_bridge;
 pub mod logger;
+pub mod zenith_proxy;
 
 use azalea::app::{PluginGroup, PluginGroupBuilder};
 
@@ -26,5 +29,6 @@ impl PluginGroup for ModulesPluginGroup {
             .add(AutoTotemPlugin)
             .add(AutoWhitelistPlugin)
             .add(LoggerPlugin)
+            .add(ZenithProxyPlugin)
     }
 }
@@ -0,0 +1,199 @@
+use std::time::{Duration, Instant};
+
+use azalea::{
+    app::{App, Plugin, Update},
+    client_chat::{ChatKind, ChatReceivedEvent},
+    ecs::prelude::*,
+    player::GameProfileComponent,
+};
+
+use crate::prelude::*;
+
+/// Replies received this long after a command are treated as `ZenithProxy` replies.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The same command isn't sent again within this long.
+const COMMAND_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Control `ZenithProxy` with its in-game commands
+pub struct ZenithProxyPlugin;
+
+impl Plugin for ZenithProxyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ZenithEvent>().add_systems(
+            Update,
+            (
+                Self::handle_zenith_events.before(MinecraftParserPlugin::handle_chat_outbox),
+                Self::handle_chat_received_events,
+            ),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ZenithAction {
+    Connect,
+    Disconnect,
+    Queue,
+    AutoReconnect(bool),
+}
+
+impl ZenithAction {
+    #[must_use]
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Connect => "connect",
+            Self::Disconnect => "disconnect",
+            Self::Queue => "queue",
+            Self::AutoReconnect(true) => "autoReconnect on",
+            Self::AutoReconnect(false) => "autoReconnect off",
+        }
+    }
+}
+
+/// Send a `ZenithProxy` in-game command on behalf of the bot.
+#[derive(Clone, Debug, Message)]
+pub struct ZenithEvent {
+    pub entity: Entity,
+    pub action: ZenithAction,
+}
+
+/// Last known `ZenithProxy` state, parsed from its command replies.
+#[derive(Clone, Component, Debug, Default)]
+pub struct ZenithStatus {
+    pub auto_reconnect: Option<bool>,
+    pub connected:      Option<bool>,
+    pub queue:          Option<String>,
+    pub last_reply:     Option<String>,
+    last_sent:          Option<(ZenithAction, Instant)>,
+}
+
+impl std::fmt::Display for ZenithStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = |value: Option<bool>, on: &str, off: &str| match value {
+            Some(true) => str!(on),
+            Some(false) => str!(off),
+            None => str!("unknown"),
+        };
+
+        write!(
+            f,
+            "zenith {}, autoreconnect {}",
+            state(self.connected, "connected", "disconnected"),
+            state(self.auto_reconnect, "on", "off"),
+        )?;
+
+        if let Some(queue) = &self.queue {
+            write!(f, ", {queue}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Marks a bot that is leaving through `ZenithProxy`, so `AutoReconnect` stays disabled.
+#[derive(Component)]
+pub struct ZenithLeave;
+
+impl ZenithProxyPlugin {
+    pub fn handle_zenith_events(
+        mut zenith_events: MessageReader<ZenithEvent>,
+        mut query: Query<(
+            &GameProfileComponent,
+            &LocalSettings,
+            &mut ChatOutbox,
+            Option<&mut ZenithStatus>,
+        )>,
+        mut commands: Commands,
+    ) {
+        for event in zenith_events.read() {
+            let Ok((game_profile, local_settings, mut chat_outbox, zenith_status)) =
+                query.get_mut(event.entity)
+            else {
+                continue;
+            };
+
+            let username = &game_profile.name;
+            let zenith_proxy = &local_settings.zenith_proxy;
+            if !zenith_proxy.enabled {
+                warn!("[{username}] ZenithProxy module is disabled");
+                continue;
+            }
+
+            let now = Instant::now();
+            let last_sent = zenith_status.as_ref().and_then(|status| status.last_sent);
+            if last_sent.is_some_and(|(action, instant)| {
+                action == event.action && now.duration_since(instant) < COMMAND_COOLDOWN
+            }) {
+                continue; /* Command Cooldown */
+            }
+
+            let command = event.action.command();
+            info!("[{username}] ZenithProxy Command: {command}");
+            let content = format!("{}{command}", zenith_proxy.command_prefix);
+            chat_outbox.push(
+                OutgoingChat {
+                    key: content.clone(),
+                    content,
+                    kind: ChatKind::Message,
+                    priority: ChatPriority::Control,
+                },
+                &local_settings.anti_spam,
+            );
+
+            match zenith_status {
+                Some(mut zenith_status) => zenith_status.last_sent = Some((event.action, now)),
+                None => {
+                    commands.entity(event.entity).insert(ZenithStatus {
+                        last_sent: Some((event.action, now)),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn handle_chat_received_events(
+        mut chat_events: MessageReader<ChatReceivedEvent>,
+        mut query: Query<(&GameProfileComponent, &mut ZenithStatus)>,
+    ) {
+        for event in chat_events.read() {
+            let Ok((game_profile, mut zenith_status)) = query.get_mut(event.entity) else {
+                continue;
+            };
+
+            let Some((_, instant)) = zenith_status.last_sent else {
+                continue;
+            };
+
+            if instant.elapsed() > REPLY_TIMEOUT {
+                continue; /* Not a reply */
+            }
+
+            let (sender, content) = event.packet.split_sender_and_content();
+            if sender.is_some() {
+                continue; /* Player Message */
+            }
+
+            let username = &game_profile.name;
+            let lowercase = content.to_lowercase();
+            info!("[{username}] ZenithProxy Reply: {content}");
+
+            if lowercase.contains("autoreconnect") {
+                if lowercase.contains(" on") || lowercase.contains("enabled") {
+                    zenith_status.auto_reconnect = Some(true);
+                } else if lowercase.contains(" off") || lowercase.contains("disabled") {
+                    zenith_status.auto_reconnect = Some(false);
+                }
+            } else if lowercase.contains("disconnect") {
+                zenith_status.connected = Some(false);
+            } else if lowercase.contains("connect") {
+                zenith_status.connected = Some(true);
+            } else if lowercase.contains("queue") {
+                zenith_status.queue = Some(content.clone());
+            }
+
+            zenith_status.last_reply = Some(content);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    io::BufRead,
+    sync::mpsc::{self, Receiver},
+};
+
+use azalea::{
+    app::{App, Plugin, Startup, Update},
+    ecs::prelude::*,
+    entity::{LocalEntity, metadata::Player},
+};
+use parking_lot::Mutex;
+
+use crate::prelude::*;
+
+/// Operator console command parsing integration
+pub struct ConsoleParserPlugin;
+
+impl Plugin for ConsoleParserPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ConsoleInput::default())
+            .add_systems(Startup, Self::handle_startup)
+            .add_systems(
+                Update,
+                (
+                    Self::handle_console_input
+                        .before(MinecraftParserPlugin::handle_chat_received_events),
+                    Self::handle_send_msg_events,
+                ),
+            );
+    }
+}
+
+/// Operator console [`CommandFrontend`], responses are printed to stdout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConsoleFrontend;
+
+impl CommandFrontend for ConsoleFrontend {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+}
+
+/// Lines read from stdin by the console reader thread.
+#[derive(Default, Resource)]
+pub struct ConsoleInput(Option<Mutex<Receiver<String>>>);
+
+impl ConsoleParserPlugin {
+    pub fn handle_startup(mut console_input: ResMut<ConsoleInput>) {
+        let (tx, rx) = mpsc::channel();
+
+        /* Stdin blocks, so it gets its own thread instead of the async runtime */
+        let spawn_result = std::thread::Builder::new()
+            .name(str!("console"))
+            .spawn(move || {
+                for line in std::io::stdin().lock().lines() {
+                    let Ok(line) = line else {
+                        break; /* Stdin Closed */
+                    };
+
+                    if tx.send(line).is_err() {
+                        break; /* Receiver Dropped */
+                    }
+                }
+            });
+
+        if let Err(error) = spawn_result {
+            error!("Failed to start console reader: {error}");
+            return;
+        }
+
+        console_input.0 = Some(Mutex::new(rx));
+    }
+
+    pub fn handle_console_input(
+        mut cmd_events: MessageWriter<CmdEvent>,
+        query: Query<(Entity, &LocalSettings), (With<Player>, With<LocalEntity>)>,
+        console_input: Res<ConsoleInput>,
+        settings: Res<GlobalSettings>,
+    ) {
+        let Some(receiver) = &console_input.0 else {
+            return; /* Console Disabled */
+        };
+
+        let lines = receiver.lock().try_iter().collect::<Vec<_>>();
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue; /* Command Missing */
+            }
+
+            let source = CmdSource::new(ConsoleFrontend);
+            let sender = CmdSender::Console;
+            let Some(events) = CmdEvent::dispatch(line, &settings, sender, source, &query) else {
+                println!("[404] Unknown command: {line}");
+                continue; /* Command Invalid */
+            };
+
+            cmd_events.write_batch(events);
+        }
+    }
+
+    pub fn handle_send_msg_events(mut msg_events: MessageReader<MsgEvent>) {
+        for event in msg_events.read() {
+            if event.source.downcast::<ConsoleFrontend>().is_none() {
+                continue;
+            }
+
+            println!("[{}] {}", event.status, event.content);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    fs::{DirBuilder, Permissions},
+    io::{BufRead, BufReader, ErrorKind, Write},
+    os::unix::{
+        fs::{DirBuilderExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use azalea::{
+    app::{App, Plugin, Startup, Update},
+    ecs::prelude::*,
+    entity::{LocalEntity, metadata::Player},
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// Local control socket command parsing integration
+pub struct ControlParserPlugin;
+
+impl Plugin for ControlParserPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ControlSocket::default())
+            .add_systems(Startup, Self::handle_startup)
+            .add_systems(
+                Update,
+                (
+                    Self::handle_control_requests
+                        .before(MinecraftParserPlugin::handle_chat_received_events),
+                    Self::handle_send_msg_events,
+                ),
+            );
+    }
+}
+
+/// Control socket [`CommandFrontend`], responses are written back to the connection.
+#[derive(Clone, Debug)]
+pub struct ControlFrontend {
+    pub reply: Sender<String>,
+}
+
+impl CommandFrontend for ControlFrontend {
+    fn name(&self) -> &'static str {
+        "control"
+    }
+}
+
+/// Newline-delimited JSON requests accepted by the control socket.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Run a command as the operator, e.g. `leave ExampleBot`.
+    Command { command: String },
+    /// Stream events as they happen, empty lists match everything.
+    Subscribe {
+        #[serde(default)]
+        types: Vec<String>,
+        #[serde(default)]
+        bots:  Vec<String>,
+    },
+}
+
+/// Newline-delimited JSON replies, subscribed events are written as-is.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlReply {
+    Reply { status: u16, content: String },
+    Error { error: String },
+}
+
+impl ControlReply {
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+type ControlCommand = (String, Sender<String>);
+
+/// Commands received by the control socket threads.
+#[derive(Default, Resource)]
+pub struct ControlSocket(Option<Mutex<Receiver<ControlCommand>>>);
+
+impl ControlSocket {
+    /// # Errors
+    /// Will return `Err` if `std::env::current_exe` or `std::env::current_dir` fails.
+    pub fn path() -> Result<PathBuf> {
+        let path = if cfg!(debug_assertions) {
+            let path = std::env::current_exe()?;
+            path.parent().context("None")?.to_path_buf()
+        } else {
+            std::env::current_dir()?
+        };
+
+        Ok(path.join("shaysbot.sock"))
+    }
+
+    /// Bind the socket, only the owner of the config directory may connect.
+    ///
+    /// The socket is bound inside a private directory and moved into place once its permissions
+    /// are restricted, so nobody else can connect in between.
+    ///
+    /// # Errors
+    /// Will return `Err` if `Self::path`, `UnixListener::bind`, or any `std::fs` call fails.
+    pub fn bind() -> Result<UnixListener> {
+        let path = Self::path()?;
+        let staging = path.with_extension("sock.d");
+        for result in [
+            std::fs::remove_file(&path),
+            std::fs::remove_dir_all(&staging),
+        ] {
+            match result {
+                Err(error) if error.kind() != ErrorKind::NotFound => bail!(error),
+                _ => {} /* Remove stale socket */
+            }
+        }
+
+        DirBuilder::new().mode(0o700).create(&staging)?;
+        let staged = staging.join("shaysbot.sock");
+        let listener = UnixListener::bind(&staged)?;
+        std::fs::set_permissions(&staged, Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, &path)?;
+        std::fs::remove_dir(&staging)?;
+
+        Ok(listener)
+    }
+}
+
+impl ControlParserPlugin {
+    pub fn handle_startup(
+        mut control_socket: ResMut<ControlSocket>,
+        subscribers: Res<EventSubscribers>,
+    ) {
+        let listener = match ControlSocket::bind() {
+            Ok(listener) => listener,
+            Err(error) => {
+                error!("Failed to start control socket: {error}");
+                return;
+            }
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let subscribers = subscribers.clone();
+        let spawn_result = std::thread::Builder::new()
+            .name(str!("control"))
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else {
+                        continue; /* Connection Failed */
+                    };
+
+                    let tx = tx.clone();
+                    let subscribers = subscribers.clone();
+                    std::thread::spawn(move || handle_connection(stream, &tx, &subscribers));
+                }
+            });
+
+        if let Err(error) = spawn_result {
+            error!("Failed to start control socket: {error}");
+            return;
+        }
+
+        if let Ok(path) = ControlSocket::path() {
+            info!("Control Socket @ {}", path.display());
+        }
+
+        control_socket.0 = Some(Mutex::new(rx));
+    }
+
+    pub fn handle_control_requests(
+        mut cmd_events: MessageWriter<CmdEvent>,
+        query: Query<(Entity, &LocalSettings), (With<Player>, With<LocalEntity>)>,
+        control_socket: Res<ControlSocket>,
+        settings: Res<GlobalSettings>,
+    ) {
+        let Some(receiver) = &control_socket.0 else {
+            return; /* Socket Disabled */
+        };
+
+        let commands = receiver.lock().try_iter().collect::<Vec<_>>();
+        for (command, reply) in commands {
+            let source = CmdSource::new(ControlFrontend {
+                reply: reply.clone(),
+            });
+
+            let sender = CmdSender::Console;
+            let Some(events) = CmdEvent::dispatch(&command, &settings, sender, source, &query)
+            else {
+                let error = format!("Unknown command: {command}");
+                let _ = reply.send(ControlReply::Error { error }.to_json());
+                continue; /* Command Invalid */
+            };
+
+            cmd_events.write_batch(events);
+        }
+    }
+
+    pub fn handle_send_msg_events(mut msg_events: MessageReader<MsgEvent>) {
+        for event in msg_events.read() {
+            let Some(ControlFrontend { reply }) = event.source.downcast::<ControlFrontend>() else {
+                continue;
+            };
+
+            let _ = reply.send(
+                ControlReply::Reply {
+                    status:  event.status,
+                    content: event.content.clone(),
+                }
+                .to_json(),
+            );
+        }
+    }
+
+    /// Send a command or subscription to a running bot and print the replies.
+    ///
+    /// # Errors
+    /// Will return `Err` if the socket can't be reached or the connection fails.
+    pub fn client(args: Vec<String>) -> Result<()> {
+        let Some(first) = args.first() else {
+            bail!("Usage: shaysbot ctl <command> | shaysbot ctl subscribe [type...]");
+        };
+
+        let path = ControlSocket::path()?;
+        let mut stream = UnixStream::connect(&path)
+            .with_context(|| format!("Failed to connect to {}", path.display()))?;
+
+        let subscribe = first == "subscribe";
+        let request = if subscribe {
+            ControlRequest::Subscribe {
+                types: args[1..].to_vec(),
+                bots:  Vec::new(),
+            }
+        } else {
+            ControlRequest::Command {
+                command: args.join(" "),
+            }
+        };
+
+        writeln!(stream, "{}", serde_json::to_string(&request)?)?;
+        if !subscribe {
+            /* Commands may be answered by several bots, wait a moment for stragglers */
+            stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+        }
+
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(0) => break, /* Connection Closed */
+                Ok(_) => {}
+                Err(error)
+                    if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    break; /* No More Replies */
+                }
+                Err(error) => bail!(error),
+            }
+
+            match serde_json::from_str::<ControlReply>(&line) {
+                Ok(ControlReply::Reply { status, content }) => println!("[{status}] {content}"),
+                Ok(ControlReply::Error { error }) => eprintln!("{error}"),
+                Err(_) => print!("{line}"), /* Subscribed Event */
+            }
+
+            if !subscribe {
+                stream.set_read_timeout(Some(Duration::from_millis(500)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn handle_connection(
+    stream: UnixStream,
+    commands: &Sender<ControlCommand>,
+    subscribers: &EventSubscribers,
+) {
+    let (reply_tx, reply_rx) = mpsc::channel::<String>();
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        for line in reply_rx {
+            if writeln!(writer, "{line}").is_err() {
+                break; /* Client Disconnected */
+            }
+        }
+    });
+
+    let mut subscriptions = Vec::new();
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break; /* Client Disconnected */
+        };
+
+        match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(ControlRequest::Command { command }) => {
+                if commands.send((command, reply_tx.clone())).is_err() {
+                    break; /* Swarm Stopped */
+                }
+            }
+            Ok(ControlRequest::Subscribe { types, bots }) => {
+                let filter = EventFilter { types, bots };
+                subscriptions.push(subscribers.subscribe(filter, reply_tx.clone()));
+            }
+            Err(error) => {
+                let error = format!("Invalid request: {error}");
+                let _ = reply_tx.send(ControlReply::Error { error }.to_json());
+            }
+        }
+    }
+
+    subscribers.unsubscribe(&subscriptions);
+}
@@ -1,25 +1,106 @@
-use std::collections::VecDeque;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
 use azalea::{
     app::{App, Plugin, Update},
     ecs::prelude::*,
     entity::{metadata::Player, LocalEntity},
+    local_player::TabList,
+    player::GameProfileComponent,
+};
+use bevy_discord::{
+    messages::bot::{DiscordInteractionCreate, DiscordMessage, DiscordReady},
+    res::DiscordHttpResource,
+    DiscordSystems,
+};
+use serenity::{
+    all::{ChannelId, CommandInteraction, Interaction, ResolvedValue, UserId},
+    json::{json, Value},
 };
-use bevy_discord::{messages::bot::DiscordMessage, res::DiscordHttpResource, DiscordSystems};
-use serenity::json::json;
+use strum::IntoEnumIterator;
+use tokio::sync::Semaphore;
 
 use crate::prelude::*;
 
+/// Discord message content limit in characters.
+pub const DISCORD_MAX_LENGTH: usize = 2000;
+
+/// Discord embed description limit in characters.
+pub const DISCORD_EMBED_MAX_LENGTH: usize = 4096;
+
+/// Discord message flag for responses only visible to the caller.
+const EPHEMERAL: u64 = 1 << 6;
+
+/// Button custom id prefix, followed by the command line to run.
+const BUTTON_PREFIX: &str = "cmd:";
+
+/// How long a deferred interaction waits for a response before a fallback is posted.
+const INTERACTION_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Discord chat command parsing integration
 pub struct DiscordParserPlugin;
 
+/// Discord [`CommandFrontend`], responses are posted to the channel the command came from.
+#[derive(Clone, Debug)]
+pub struct DiscordFrontend {
+    pub channel_id:  ChannelId,
+    /// Command line without the prefix, used to build follow-up buttons.
+    pub command:     String,
+    /// Slash command or button the responses are sent to as followups, instead of the channel.
+    pub interaction: Option<SlashInteraction>,
+}
+
+impl CommandFrontend for DiscordFrontend {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Discord direct message [`CommandFrontend`], responses are sent back to the DM channel.
+#[derive(Clone, Debug)]
+pub struct DiscordDmFrontend {
+    pub channel_id: ChannelId,
+    /// Command line without the prefix, used to build follow-up buttons.
+    pub command:    String,
+}
+
+impl CommandFrontend for DiscordDmFrontend {
+    fn name(&self) -> &'static str {
+        "discord_dm"
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+}
+
+/// A deferred slash command or button interaction.
+#[derive(Clone, Debug)]
+pub struct SlashInteraction {
+    pub token:     String,
+    pub ephemeral: bool,
+    /// Gets a permit once the deferred response is sent, followups take turns with it.
+    deferred:      Arc<Semaphore>,
+    /// Whether any bot responded, otherwise the deferred response would never resolve.
+    responded:     Arc<AtomicBool>,
+}
+
 impl Plugin for DiscordParserPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
             (
+                Self::handle_ready_events.after(DiscordSystems),
+                Self::handle_interaction_events.after(DiscordSystems),
                 Self::handle_message_events.after(DiscordSystems),
                 Self::handle_send_msg_events,
+                Self::handle_chamber_events,
             ),
         );
     }
@@ -29,33 +110,29 @@ impl DiscordParserPlugin {
     pub fn handle_message_events(
         mut message_events: MessageReader<DiscordMessage>,
         mut cmd_events: MessageWriter<CmdEvent>,
-        query: Query<Entity, (With<Player>, With<LocalEntity>)>,
+        query: Query<(Entity, &LocalSettings), (With<Player>, With<LocalEntity>)>,
         settings: Res<GlobalSettings>,
     ) {
         for event in message_events.read() {
             let message = event.new_message.clone();
-            let mut args = message
-                .content
-                .split(' ')
-                .map(String::from)
-                .collect::<VecDeque<_>>();
-            let Some(alias) = args.pop_front() else {
-                continue; /* Command Missing */
+            let Some((cmd, args)) = Cmds::parse(&message.content, &settings.command_prefix) else {
+                continue; /* Command Invalid */
             };
 
-            let Some(cmd) = Cmds::find(&alias.replace(&settings.command_prefix, "")) else {
-                continue; /* Command Invalid */
+            /* Direct messages are only accepted from linked users */
+            let is_direct = message.guild_id.is_none();
+            let sender = CmdSender::Discord(message.author.id.get());
+            let is_allowed = if is_direct {
+                let user_id = str!(message.author.id);
+                settings.users.values().any(|user| user.discord_id == user_id)
+            } else {
+                sender.is_whitelisted(&settings)
             };
 
-            if settings.whitelist_only
-                && !settings
-                    .users
-                    .iter()
-                    .any(|(_, user)| user.discord_id == message.author.id.to_string())
-            {
+            if !is_allowed {
                 let is_whitelist_link = matches!(
                     (cmd, args.front().map(String::as_str)),
-                    (Cmds::Whitelist(_), Some("link"))
+                    (Cmds::Whitelist(_), Some("link")) | (Cmds::Verify(_), _)
                 );
 
                 if !is_whitelist_link {
@@ -66,8 +143,8 @@ impl DiscordParserPlugin {
                     tokio::task::spawn_local(async move {
                         let content = format!(
                             "Your Discord and Minecraft accounts are not currently linked.\n\
-                            To link via in-game, message the bot the following command: `{prefix}whitelist link {user_id}`\n\
-                            To link via Discord, run the following command with your `auth.aristois.net` code `{prefix}whitelist link <code>`"
+                            To link via Discord, run `/whitelist link` or DM me `{prefix}whitelist link` and whisper the code to any bot in-game\n\
+                            To link via in-game, message the bot `{prefix}whitelist link {user_id}` and confirm the code here"
                         );
 
                         if let Err(error) = http
@@ -86,26 +163,209 @@ impl DiscordParserPlugin {
                 }
             }
 
-            let mut cmd_event = CmdEvent {
-                args: args.clone(),
+            let channel_id = message.channel_id;
+            let command = message
+                .content
+                .trim_start_matches(settings.command_prefix.as_str())
+                .to_string();
+
+            let source = if is_direct {
+                CmdSource::new(DiscordDmFrontend {
+                    channel_id,
+                    command,
+                })
+            } else {
+                CmdSource::new(DiscordFrontend {
+                    channel_id,
+                    command,
+                    interaction: None,
+                })
+            };
+
+            let cmd_event = CmdEvent {
+                args,
                 cmd,
                 entity: None,
                 message: false,
-                source: CmdSource::Discord(message.channel_id),
-                sender: CmdSender::Discord(message.author.id),
+                sender,
+                source,
             };
 
-            cmd_events.write_batch(std::iter::once(cmd_event.clone()).chain(query.iter().map(
-                |entity| {
-                    cmd_event.entity = Some(entity);
-                    cmd_event.clone()
-                },
-            )));
+            cmd_events.write_batch(cmd_event.broadcast(&query, &settings));
+        }
+    }
+
+    pub fn handle_ready_events(mut ready_events: MessageReader<DiscordReady>) {
+        for event in ready_events.read() {
+            let http = event.ctx.http.clone();
+            let commands = Cmds::iter().map(slash_command).collect::<Vec<_>>();
+
+            tokio::task::spawn_local(async move {
+                match http.create_global_commands(&commands).await {
+                    Ok(commands) => info!("[Discord] Registered {} slash commands", commands.len()),
+                    Err(error) => error!("[Discord] Failed to register slash commands: {error}"),
+                }
+            });
+        }
+    }
+
+    pub fn handle_interaction_events(
+        mut interaction_events: MessageReader<DiscordInteractionCreate>,
+        mut cmd_events: MessageWriter<CmdEvent>,
+        query: Query<(Entity, &LocalSettings), (With<Player>, With<LocalEntity>)>,
+        bots: Query<(&TabList, &LocalSettings)>,
+        settings: Res<GlobalSettings>,
+    ) {
+        for event in interaction_events.read() {
+            let http = event.ctx.http.clone();
+            let (id, token, user, channel_id, line) = match &event.interaction {
+                Interaction::Autocomplete(command) => {
+                    let choices = autocomplete(command, &bots, &settings);
+                    let map = json!({ "type": 8, "data": { "choices": choices } });
+                    let (id, token) = (command.id, command.token.clone());
+
+                    tokio::task::spawn_local(async move {
+                        if let Err(error) =
+                            http.create_interaction_response(id, &token, &map, vec![]).await
+                        {
+                            error!("{error}");
+                        }
+                    });
+
+                    continue;
+                }
+                Interaction::Command(command) => {
+                    /* Options are read by name, so they keep the in-game argument order */
+                    let options = command.data.options();
+                    let args = ["player", "location", "args"]
+                        .into_iter()
+                        .filter_map(|name| options.iter().find(|option| option.name == name))
+                        .filter_map(|option| match option.value {
+                            ResolvedValue::String(value) => Some(value),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+
+                    let line = format!("{} {}", command.data.name, args.join(" "));
+                    (command.id, &command.token, &command.user, command.channel_id, line)
+                }
+                Interaction::Component(component) => {
+                    let Some(line) = component.data.custom_id.strip_prefix(BUTTON_PREFIX) else {
+                        continue; /* Not A Command Button */
+                    };
+
+                    let line = str!(line);
+                    (component.id, &component.token, &component.user, component.channel_id, line)
+                }
+                _ => continue,
+            };
+
+            let Some((cmd, args)) = Cmds::parse(line.trim(), "") else {
+                continue; /* Command Invalid */
+            };
+
+            let sender = CmdSender::Discord(user.id.get());
+            let is_whitelist_link = matches!(
+                (cmd, args.front().map(String::as_str)),
+                (Cmds::Whitelist(_), Some("link")) | (Cmds::Verify(_), _)
+            );
+
+            /* Keep pearl requests, account links, and settings out of public channels */
+            let ephemeral = matches!(
+                cmd,
+                Cmds::Account(_)
+                    | Cmds::Config(_)
+                    | Cmds::Pearl(_)
+                    | Cmds::Verify(_)
+                    | Cmds::Whitelist(_)
+            );
+
+            let flags = if ephemeral { EPHEMERAL } else { 0 };
+            let token = token.clone();
+            if !sender.is_whitelisted(&settings) && !is_whitelist_link {
+                let prefix = &settings.command_prefix;
+                let user_id = user.id;
+                let content = format!(
+                    "Your Discord and Minecraft accounts are not currently linked.\n\
+                    To link via Discord, run `/whitelist link` and whisper the code to any bot in-game\n\
+                    To link via in-game, message the bot `{prefix}whitelist link {user_id}` and confirm the code here"
+                );
+
+                let map = json!({ "type": 4, "data": { "content": content, "flags": EPHEMERAL } });
+                tokio::task::spawn_local(async move {
+                    if let Err(error) =
+                        http.create_interaction_response(id, &token, &map, vec![]).await
+                    {
+                        error!("{error}");
+                    }
+                });
+
+                continue;
+            }
+
+            let interaction = SlashInteraction {
+                token: token.clone(),
+                ephemeral,
+                deferred: Arc::new(Semaphore::new(0)),
+                responded: Arc::new(AtomicBool::new(false)),
+            };
+
+            let deferred = interaction.deferred.clone();
+            let responded = interaction.responded.clone();
+            let map = json!({ "type": 5, "data": { "flags": flags } });
+            tokio::task::spawn_local(async move {
+                if let Err(error) =
+                    http.create_interaction_response(id, &token, &map, vec![]).await
+                {
+                    error!("{error}");
+                    deferred.close();
+                    return;
+                }
+
+                deferred.add_permits(1);
+                tokio::time::sleep(INTERACTION_TIMEOUT).await;
+                if responded.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                /* No bot handled the command, e.g. none are online */
+                let Ok(_permit) = deferred.acquire().await else {
+                    return;
+                };
+
+                let map = json!({
+                    "embeds": [{
+                        "description": "No bot responded to this command",
+                        "color": status_color(500),
+                    }],
+                    "flags": flags,
+                });
+
+                if let Err(error) = http.create_followup_message(&token, &map, Vec::new()).await {
+                    error!("{error}");
+                }
+            });
+
+            let cmd_event = CmdEvent {
+                args,
+                cmd,
+                entity: None,
+                message: false,
+                sender,
+                source: CmdSource::new(DiscordFrontend {
+                    channel_id,
+                    command: str!(line.trim()),
+                    interaction: Some(interaction),
+                }),
+            };
+
+            cmd_events.write_batch(cmd_event.broadcast(&query, &settings));
         }
     }
 
     pub fn handle_send_msg_events(
         mut msg_events: MessageReader<MsgEvent>,
+        query: Query<(&GameProfileComponent, &LocalSettings, Option<&QueueStatus>)>,
         discord: Option<Res<DiscordHttpResource>>,
     ) {
         let Some(discord) = discord else {
@@ -113,21 +373,284 @@ impl DiscordParserPlugin {
         };
 
         for event in msg_events.read() {
+            let (channel_id, command, interaction) =
+                if let Some(frontend) = event.source.downcast::<DiscordFrontend>() {
+                    let interaction = frontend.interaction.clone();
+                    (frontend.channel_id, &frontend.command, interaction)
+                } else if let Some(frontend) = event.source.downcast::<DiscordDmFrontend>() {
+                    (frontend.channel_id, &frontend.command, None)
+                } else {
+                    continue;
+                };
+
+            let mut fields = Vec::new();
+            if let Some(Ok((game_profile, local_settings, queue_status))) =
+                event.entity.map(|entity| query.get(entity))
+            {
+                let location = match local_settings.server.as_str() {
+                    "" => local_settings.auto_pearl.location.clone(),
+                    server => format!("{server}:{}", local_settings.auto_pearl.location),
+                };
+
+                fields.push(json!({ "name": "Bot", "value": game_profile.name, "inline": true }));
+                fields.push(json!({ "name": "Location", "value": location, "inline": true }));
+                if let Some(queue_status) = queue_status {
+                    let value = queue_status.to_string();
+                    fields.push(json!({ "name": "Queue", "value": value, "inline": true }));
+                }
+            }
+
+            let color = status_color(event.status);
+            let mut embeds = split_message(&event.content, DISCORD_EMBED_MAX_LENGTH)
+                .into_iter()
+                .map(|description| json!({ "description": description, "color": color }))
+                .collect::<Vec<_>>();
+
+            if let Some(embed) = embeds.last_mut() {
+                embed["fields"] = json!(fields);
+            }
+
+            if let Some(interaction) = &interaction {
+                interaction.responded.store(true, Ordering::Relaxed);
+            }
+
+            let components = buttons(command, event.status);
             let client = discord.client();
-            let content = event.content.clone();
-            let CmdSource::Discord(channel_id) = event.source else {
+            let count = embeds.len();
+            tokio::task::spawn_local(async move {
+                for (index, embed) in embeds.into_iter().enumerate() {
+                    let mut map = json!({
+                        "embeds": [embed],
+                        "allowed_mentions": { "parse": [] },
+                    });
+
+                    if index + 1 == count && !components.is_empty() {
+                        map["components"] = json!([{ "type": 1, "components": components }]);
+                    }
+
+                    let result = if let Some(interaction) = &interaction {
+                        let Ok(_permit) = interaction.deferred.acquire().await else {
+                            break; /* Defer Failed */
+                        };
+
+                        if interaction.ephemeral {
+                            map["flags"] = json!(EPHEMERAL);
+                        }
+
+                        client
+                            .create_followup_message(&interaction.token, &map, Vec::new())
+                            .await
+                    } else {
+                        client.send_message(channel_id, Vec::new(), &map).await
+                    };
+
+                    if let Err(error) = result {
+                        error!("{error}");
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Notify chamber owners that opted in with the `notify` command in their DMs.
+    pub fn handle_chamber_events(
+        mut chamber_events: MessageReader<ChamberEvent>,
+        query: Query<(&GameProfileComponent, &LocalSettings)>,
+        settings: Res<GlobalSettings>,
+        discord: Option<Res<DiscordHttpResource>>,
+    ) {
+        let Some(discord) = discord else {
+            return;
+        };
+
+        for event in chamber_events.read() {
+            if !settings.dm_notifications.contains(&event.owner_uuid) {
+                continue; /* Not Opted In */
+            }
+
+            let Some(user_id) = settings
+                .users
+                .get(&event.owner_uuid)
+                .and_then(|user| user.discord_id.parse::<u64>().ok())
+                .map(UserId::new)
+            else {
+                continue; /* Not Linked */
+            };
+
+            let Ok((game_profile, local_settings)) = query.get(event.entity) else {
                 continue;
             };
 
+            let bot = &game_profile.name;
+            let location = match local_settings.server.as_str() {
+                "" => local_settings.auto_pearl.location.clone(),
+                server => format!("{server}:{}", local_settings.auto_pearl.location),
+            };
+
+            let (status, description) = match event.kind {
+                ChamberEventKind::Registered => (200, "Your pearl was registered"),
+                ChamberEventKind::Broken => (500, "Your stasis chamber was broken"),
+                ChamberEventKind::Pulled => (200, "Your pearl was pulled"),
+            };
+
+            let position = event.block_pos.to_string();
+            let map = json!({
+                "embeds": [{
+                    "description": description,
+                    "color": status_color(status),
+                    "fields": [
+                        { "name": "Bot", "value": bot, "inline": true },
+                        { "name": "Location", "value": location, "inline": true },
+                        { "name": "Position", "value": position, "inline": true },
+                    ],
+                }],
+            });
+
+            let client = discord.client();
             tokio::task::spawn_local(async move {
-                let map = &json!({
-                    "content": content,
-                });
+                let recipient = &json!({ "recipient_id": user_id });
+                let result = match client.create_private_channel(recipient).await {
+                    Ok(channel) => client.send_message(channel.id, Vec::new(), &map).await,
+                    Err(error) => Err(error),
+                };
 
-                if let Err(error) = client.send_message(channel_id, Vec::new(), map).await {
-                    error!("{error}");
+                if let Err(error) = result {
+                    error!("[Discord] Failed to DM {user_id}: {error}");
                 }
             });
         }
     }
 }
+
+/// Embed color for an HTTP-like response status.
+const fn status_color(status: u16) -> u32 {
+    match status {
+        200..300 => 0x0057_F287, /* Green */
+        400..500 => 0x00FE_E75C, /* Yellow */
+        500..600 => 0x00ED_4245, /* Red */
+        _ => 0x0058_65F2,        /* Blurple */
+    }
+}
+
+/// Follow-up action buttons for a command response, each runs a command line when clicked.
+fn buttons(command: &str, status: u16) -> Vec<Value> {
+    let Some((Cmds::Pearl(_), args)) = Cmds::parse(command, "") else {
+        return Vec::new();
+    };
+
+    if is_locations_list(&args) {
+        return Vec::new(); /* Nothing to cancel or pull again */
+    }
+
+    let button = |label: &str, style: u8, line: String| {
+        json!({
+            "type": 2,
+            "style": style,
+            "label": label,
+            "custom_id": format!("{BUTTON_PREFIX}{line}"),
+        })
+    };
+
+    let args = args
+        .into_iter()
+        .filter(|arg| arg != "confirm")
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let player = args.split(' ').next().unwrap_or_default();
+    let buttons = match status {
+        200 if !args.contains("cancel") => vec![
+            button("Cancel", 4, format!("pearl {player} cancel")),
+            button("Pull another", 1, format!("pearl {args}")),
+        ],
+        409 => vec![button("Confirm shared pull", 3, format!("pearl {args} confirm"))],
+        _ => Vec::new(),
+    };
+
+    /* Discord limits custom ids to 100 characters */
+    buttons
+        .into_iter()
+        .filter(|button| button["custom_id"].as_str().is_some_and(|id| id.len() <= 100))
+        .collect()
+}
+
+/// Build a slash command from a command plugin, pearl gets autocompleted options.
+fn slash_command(cmds: Cmds) -> Value {
+    let cmd = cmds.cmd();
+    let options = match cmds {
+        Cmds::Pearl(_) => json!([
+            {
+                "type": 3,
+                "name": "player",
+                "description": "Player to pull the pearl of",
+                "required": true,
+                "autocomplete": true,
+            },
+            {
+                "type": 3,
+                "name": "location",
+                "description": "Stasis chamber location",
+                "autocomplete": true,
+            },
+        ]),
+        _ => json!([
+            {
+                "type": 3,
+                "name": "args",
+                "description": "Command arguments",
+            },
+        ]),
+    };
+
+    json!({
+        "name": cmd.aliases()[0],
+        "description": cmd.description(),
+        "options": options,
+    })
+}
+
+/// Suggest locations from connected bots, and players from the caller's linked accounts.
+fn autocomplete(
+    command: &CommandInteraction,
+    bots: &Query<(&TabList, &LocalSettings)>,
+    settings: &GlobalSettings,
+) -> Vec<Value> {
+    let Some(option) = command.data.autocomplete() else {
+        return Vec::new();
+    };
+
+    let mut choices = match option.name {
+        "location" => bots
+            .iter()
+            .map(|(_, ls)| match ls.server.as_str() {
+                "" => ls.auto_pearl.location.clone(),
+                server => format!("{server}:{}", ls.auto_pearl.location),
+            })
+            .collect::<Vec<_>>(),
+        "player" => {
+            let user_id = command.user.id.to_string();
+            settings
+                .users
+                .iter()
+                .filter(|(_, user)| user.discord_id == user_id)
+                .map(|(uuid, _)| {
+                    bots.iter()
+                        .find_map(|(tab_list, _)| tab_list.get(uuid))
+                        .map_or_else(|| uuid.to_string(), |info| info.profile.name.clone())
+                })
+                .collect::<Vec<_>>()
+        }
+        _ => Vec::new(),
+    };
+
+    let value = option.value.to_lowercase();
+    choices.retain(|choice| choice.to_lowercase().contains(&value));
+    choices.sort();
+    choices.dedup();
+    choices
+        .into_iter()
+        .take(25) /* Discord Limit */
+        .map(|choice| json!({ "name": choice, "value": choice }))
+        .collect()
+}
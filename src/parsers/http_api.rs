@@ -1,40 +1,451 @@
-use std::{collections::VecDeque, io::Read, result::Result, str::FromStr, sync::Mutex};
+use std::{
+    fs::OpenOptions,
+    io::{Cursor, Read, Write},
+    result::Result,
+    str::FromStr,
+    sync::{Mutex, mpsc},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use azalea::{
     app::{App, Plugin, Startup, Update},
     ecs::prelude::*,
     local_player::TabList,
+    player::GameProfileComponent,
 };
 use base64::{Engine, prelude::BASE64_STANDARD};
-use serde::{Deserialize, Serialize};
-use tiny_http::{Header, Request, Response, Server};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
 use uuid::Uuid;
 
-use crate::prelude::*;
+use crate::{prelude::*, settings::global::ApiServer as ApiServerSettings};
+
+/// Interval between Server-Sent Events comments keeping idle streams open.
+pub const EVENT_KEEP_ALIVE: Duration = Duration::from_secs(15);
 
 #[derive(Default, Resource)]
 pub struct ApiServer(Option<Server>);
 
+/// How long Mojang username lookups are cached for API logins.
+const UUID_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Failed API logins by IP address and username, with exponential lockouts.
+#[derive(Default, Resource)]
+pub struct ApiGuard {
+    failures: HashMap<String, ApiFailures>,
+    uuids:    HashMap<String, (Result<Uuid, (u16, String)>, Instant)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ApiFailures {
+    count:        u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+/// Fired when repeated failed API logins lock out an IP address or username.
+#[derive(Clone, Debug, Message)]
+pub struct ApiAlertEvent {
+    /// The locked out key. (`ip:<address>` or `user:<name>`)
+    pub key:      String,
+    pub failures: u32,
+    pub lockout:  Duration,
+}
+
+/// A line in the API audit log.
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiAuditEntry<'a> {
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+    pub ip:        &'a str,
+    pub user:      &'a str,
+    pub action:    &'a str,
+    pub result:    &'a str,
+}
+
+/// Basic-auth logins waiting on the login thread, argon2 is too slow for the main thread.
+#[derive(Default, Resource)]
+pub struct ApiLogins(Option<(mpsc::Sender<ApiLogin>, Mutex<mpsc::Receiver<ApiLogin>>)>);
+
+/// A login that is finished once its password, if any, is verified.
+pub struct ApiLogin {
+    request:  Request,
+    ip:       String,
+    user:     String,
+    result:   Result<(Uuid, ApiScope), (u16, String)>,
+    /// Password and argon2 hash still to be verified.
+    password: Option<(String, String)>,
+}
+
+impl ApiLogin {
+    fn verify(&mut self) {
+        if let Some((password, hash)) = self.password.take()
+            && !verify_secret(&password, &hash)
+        {
+            warn!("[API] {} tried an incorrect password!", self.user);
+            self.result = Err((401, str!("Incorrect password")));
+        }
+    }
+}
+
+impl ApiLogins {
+    /// Start the login thread, logins are verified one at a time.
+    ///
+    /// # Errors
+    /// Will return `Err` if `std::thread::Builder::spawn` fails.
+    pub fn start() -> std::io::Result<Self> {
+        let (job_tx, job_rx) = mpsc::channel::<ApiLogin>();
+        let (result_tx, result_rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name(str!("api-login"))
+            .spawn(move || {
+                for mut login in job_rx {
+                    login.verify();
+                    if result_tx.send(login).is_err() {
+                        break; /* Server Stopped */
+                    }
+                }
+            })?;
+
+        Ok(Self(Some((job_tx, Mutex::new(result_rx)))))
+    }
+
+    /// Queue a login for the login thread, it's verified right away if the thread isn't running.
+    fn submit(&self, mut login: ApiLogin) -> Option<ApiLogin> {
+        let Some((jobs, _)) = &self.0 else {
+            login.verify();
+            return Some(login);
+        };
+
+        jobs.send(login).err().map(|mpsc::SendError(mut login)| {
+            login.verify();
+            login
+        })
+    }
+
+    /// Logins the login thread has finished verifying.
+    fn finished(&self) -> Vec<ApiLogin> {
+        self.0
+            .as_ref()
+            .and_then(|(_, results)| results.lock().ok().map(|rx| rx.try_iter().collect()))
+            .unwrap_or_default()
+    }
+}
+
+impl ApiGuard {
+    /// Remaining lockout for any of the keys.
+    #[must_use]
+    pub fn locked_for(&self, keys: &[String]) -> Option<Duration> {
+        let now = Instant::now();
+        keys.iter()
+            .filter_map(|key| self.failures.get(key)?.locked_until)
+            .filter(|locked_until| *locked_until > now)
+            .map(|locked_until| locked_until - now)
+            .max()
+    }
+
+    /// Count a failed login, returns the new lockout once a key reaches `max_failures`.
+    pub fn fail(&mut self, key: &str, api_server: &ApiServerSettings) -> Option<(u32, Duration)> {
+        let now = Instant::now();
+        self.failures
+            .retain(|_, failures| now - failures.last_failure < api_server.max_lockout);
+
+        let failures = self.failures.entry(key.to_owned()).or_insert(ApiFailures {
+            count:        0,
+            last_failure: now,
+            locked_until: None,
+        });
+
+        failures.count += 1;
+        failures.last_failure = now;
+        if failures.count < api_server.max_failures {
+            return None;
+        }
+
+        /* Double the lockout for every failure past the limit */
+        let exponent = (failures.count - api_server.max_failures).min(16);
+        let lockout = api_server
+            .lockout
+            .saturating_mul(2_u32.pow(exponent))
+            .min(api_server.max_lockout);
+
+        failures.locked_until = Some(now + lockout);
+        Some((failures.count, lockout))
+    }
+
+    /// Reset the failures of a key after a successful login.
+    pub fn succeed(&mut self, key: &str) {
+        self.failures.remove(key);
+    }
+
+    /// Resolve a username with Mojang, caching the result so retries don't hit Mojang.
+    ///
+    /// # Errors
+    /// Will return `Err` if `fetch_uuid` fails.
+    pub fn resolve_uuid(&mut self, username: &str) -> Result<Uuid, (u16, String)> {
+        let now = Instant::now();
+        self.uuids
+            .retain(|_, (_, fetched_at)| now - *fetched_at < UUID_CACHE_TTL);
+
+        if let Some((result, _)) = self.uuids.get(username) {
+            return result.clone();
+        }
+
+        let result = fetch_uuid(username);
+        if matches!(result, Ok(_) | Err((404, _))) {
+            self.uuids
+                .insert(username.to_owned(), (result.clone(), now));
+        }
+
+        result
+    }
+}
+
 /// Local HTTP API command parsing integration
 pub struct HttpApiParserPlugin;
 
+/// Machine-readable `OpenAPI` schema of the routes, served at `/schema`.
+pub const API_SCHEMA: &str = include_str!("http_api.openapi.json");
+
+/// Most queued requests accepted in a single frame.
+const MAX_REQUESTS_PER_FRAME: usize = 64;
+
+/// How long API jobs are kept after they're created.
+const JOB_TTL: Duration = Duration::from_secs(3600);
+
+pub type BotQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GameProfileComponent,
+        &'static LocalSettings,
+        Option<&'static QueueStatus>,
+    ),
+>;
+
+/// HTTP API [`CommandFrontend`], the first response answers the pending request.
+#[derive(Clone, Debug)]
+pub struct ApiFrontend {
+    pub request: Arc<Mutex<Option<Request>>>,
+    /// Reply with a JSON object instead of plain text.
+    pub json:    bool,
+    /// Complete a job instead of answering the request.
+    pub job:     Option<u64>,
+}
+
+/// An authenticated API request waiting to be routed.
+#[derive(Clone, Debug, Message)]
+pub struct ApiRequestEvent {
+    pub request: Arc<Mutex<Option<Request>>>,
+    pub uuid:    Uuid,
+    pub scope:   ApiScope,
+    pub ip:      String,
+    pub user:    String,
+}
+
+/// Requests waiting for a command response, answered with 504 after their deadline.
+#[derive(Default, Deref, DerefMut, Resource)]
+pub struct ApiPending(Vec<(Arc<Mutex<Option<Request>>>, Instant)>);
+
+/// Commands started with `POST /pearl`, polled with `GET /jobs/{id}`.
+#[derive(Default, Resource)]
+pub struct ApiJobs {
+    next_id: u64,
+    jobs:    HashMap<u64, ApiJob>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiJob {
+    pub id:      u64,
+    #[serde(skip)]
+    pub owner:   Uuid,
+    pub command: String,
+    pub state:   ApiJobState,
+    pub status:  Option<u16>,
+    pub message: Option<String>,
+    /// Unix timestamp in seconds.
+    pub created: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiJobState {
+    Pending,
+    Done,
+}
+
+impl ApiJobs {
+    /// Start a pending job, forgetting jobs older than an hour.
+    pub fn create(&mut self, owner: Uuid, command: &str) -> ApiJob {
+        let created = unix_timestamp();
+        self.jobs
+            .retain(|_, job| created.saturating_sub(job.created) < JOB_TTL.as_secs());
+
+        self.next_id += 1;
+        let job = ApiJob {
+            id: self.next_id,
+            owner,
+            command: command.to_owned(),
+            state: ApiJobState::Pending,
+            status: None,
+            message: None,
+            created,
+        };
+
+        self.jobs.insert(job.id, job.clone());
+        job
+    }
+
+    /// Finish a pending job with the first response, later responses are ignored.
+    pub fn complete(&mut self, id: u64, status: u16, message: &str) {
+        let Some(job) = self.jobs.get_mut(&id) else {
+            return;
+        };
+
+        if job.state == ApiJobState::Pending {
+            job.state = ApiJobState::Done;
+            job.status = Some(status);
+            job.message = Some(message.to_owned());
+        }
+    }
+
+    /// Finish pending jobs that got no response within the timeout.
+    pub fn expire(&mut self, timeout: Duration) {
+        let now = unix_timestamp();
+        for job in self.jobs.values_mut() {
+            let is_expired = now.saturating_sub(job.created) >= timeout.as_secs();
+            if job.state == ApiJobState::Pending && is_expired {
+                job.state = ApiJobState::Done;
+                job.status = Some(504);
+                job.message = Some(str!("Timed out waiting for a response"));
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, id: u64) -> Option<&ApiJob> {
+        self.jobs.get(&id)
+    }
+}
+
+/// A bot as returned by `GET /bots`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiBot<'a> {
+    pub name:       &'a str,
+    pub uuid:       Uuid,
+    pub server:     &'a str,
+    pub location:   &'a str,
+    pub auto_pearl: bool,
+    pub queue:      Option<&'a QueueStatus>,
+}
+
+impl<'a>
+    From<(
+        &'a GameProfileComponent,
+        &'a LocalSettings,
+        Option<&'a QueueStatus>,
+    )> for ApiBot<'a>
+{
+    fn from(
+        (profile, local_settings, queue): (
+            &'a GameProfileComponent,
+            &'a LocalSettings,
+            Option<&'a QueueStatus>,
+        ),
+    ) -> Self {
+        Self {
+            name: &profile.name,
+            uuid: profile.uuid,
+            server: &local_settings.server,
+            location: &local_settings.auto_pearl.location,
+            auto_pearl: local_settings.auto_pearl.enabled,
+            queue,
+        }
+    }
+}
+
+/// A pearl location as returned by `GET /locations`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiLocation {
+    pub server:   String,
+    pub location: String,
+    pub bots:     Vec<String>,
+    pub chambers: usize,
+}
+
+/// A whitelisted player as returned by `GET /whitelist`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiWhitelistEntry<'a> {
+    pub uuid:         Uuid,
+    pub discord_id:   &'a str,
+    pub api_password: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct PearlRequest {
+    location: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct WhitelistRequest {
+    player: String,
+}
+
+enum ApiReply {
+    Json(u16, Value),
+    Error(u16, String),
+    Command(String, ApiReplyKind),
+    Stream(EventFilter),
+    /// Plain text body with its content type.
+    Text(&'static str, String),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ApiReplyKind {
+    /// The first response answers in plain text. (`/cmd`, `/account`)
+    Text,
+    /// The first response answers in JSON.
+    Json,
+    /// Answer with a job right away, the first response completes it.
+    Job,
+}
+
+impl CommandFrontend for ApiFrontend {
+    fn name(&self) -> &'static str {
+        "api"
+    }
+}
+
 impl Plugin for HttpApiParserPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ApiServer::default())
+            .init_resource::<ApiGuard>()
+            .init_resource::<ApiJobs>()
+            .init_resource::<ApiLogins>()
+            .init_resource::<ApiPending>()
+            .add_message::<ApiAlertEvent>()
+            .add_message::<ApiRequestEvent>()
             .add_systems(Startup, Self::handle_startup)
             .add_systems(
                 Update,
                 (
-                    Self::handle_api_requests
+                    (Self::handle_api_requests, Self::handle_api_request_events)
+                        .chain()
                         .before(MinecraftParserPlugin::handle_chat_received_events),
-                    Self::handle_send_msg_events,
+                    (Self::handle_send_msg_events, Self::handle_api_deadlines).chain(),
                 ),
             );
     }
 }
 
 impl HttpApiParserPlugin {
-    pub fn handle_startup(mut api_server: ResMut<ApiServer>, settings: Res<GlobalSettings>) {
+    pub fn handle_startup(
+        mut api_server: ResMut<ApiServer>,
+        mut api_logins: ResMut<ApiLogins>,
+        settings: Res<GlobalSettings>,
+    ) {
         match Server::http(settings.http_api.bind_addr.clone()) {
             Ok(server) => {
                 info!("API Server @ {}", server.server_addr());
@@ -42,16 +453,24 @@ impl HttpApiParserPlugin {
             }
             Err(error) => {
                 error!("Failed to start API server: {error}");
+                return;
             }
         }
+
+        match ApiLogins::start() {
+            Ok(logins) => *api_logins = logins,
+            Err(error) => error!("Failed to start API login thread: {error}"),
+        }
     }
 
     /// # Panics
     /// Will panic if `Header::from_str` fails.
     pub fn handle_api_requests(
-        mut cmd_events: MessageWriter<CmdEvent>,
-        query: Query<Entity>,
+        mut alert_events: MessageWriter<ApiAlertEvent>,
+        mut request_events: MessageWriter<ApiRequestEvent>,
         api_server: ResMut<ApiServer>,
+        mut api_guard: ResMut<ApiGuard>,
+        api_logins: Res<ApiLogins>,
         settings: Res<GlobalSettings>,
         tab_list: Res<TabList>,
     ) {
@@ -60,117 +479,404 @@ impl HttpApiParserPlugin {
             return;
         };
 
-        let Ok(Some(request)) = server.try_recv() else {
-            return; /* No API Request */
-        };
+        for login in api_logins.finished() {
+            let events = (&mut alert_events, &mut request_events);
+            finish_login(login, &mut api_guard, &settings, events);
+        }
 
-        let Some(header) = request
-            .headers()
-            .iter()
-            .find(|header| header.field.equiv("Authorization"))
-        else {
-            let header = Header::from_str("WWW-Authenticate: Basic").unwrap();
-            let response = Response::from_string("Unauthorized")
-                .with_status_code(401)
-                .with_header(header);
+        /* Drain a batch of queued requests each frame */
+        for _ in 0..MAX_REQUESTS_PER_FRAME {
+            let Ok(Some(request)) = server.try_recv() else {
+                break; /* No API Request */
+            };
 
-            send_response(request, response);
-            return;
-        };
+            if request.url() == "/schema" {
+                send_json(request, str!(API_SCHEMA));
+                continue; /* Public Schema */
+            }
 
-        let encoded = header.value.as_str().replace("Basic ", "");
-        let Ok(bytes) = BASE64_STANDARD.decode(&encoded) else {
-            send_text(request, "Invalid BASE64", 406);
-            return;
-        };
+            let Some(header) = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Authorization"))
+            else {
+                let header = Header::from_str("WWW-Authenticate: Basic").unwrap();
+                let response = error_response("Unauthorized", 401).with_header(header);
 
-        let Ok(credentials) = String::from_utf8(bytes) else {
-            send_text(request, "Invalid UTF-8", 406);
-            return;
-        };
+                send_response(request, response);
+                continue;
+            };
 
-        // RFC 2617 provides support for passwords with colons
-        let Some((username, password)) = credentials.split_once(':') else {
-            send_text(request, "Invalid Format", 406);
-            return;
-        };
+            let ip = request
+                .remote_addr()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_default();
+            let user = auth_identity(header.value.as_str());
+            let keys = [format!("ip:{ip}"), format!("user:{user}")];
+            if let Some(remaining) = api_guard.locked_for(&keys) {
+                audit(&settings, &ip, &user, "login", "locked");
+                send_locked(request, remaining);
+                continue;
+            }
 
-        let uuid = if let Some((uuid, _info)) = tab_list
-            .iter()
-            .find(|(_, info)| info.profile.name.to_lowercase() == username.to_lowercase())
-        {
-            *uuid
-        } else {
-            match fetch_uuid(&username.to_lowercase()) {
-                Ok(uuid) => uuid,
-                Err((status, content)) => {
-                    warn!("[API] {username} tried but {content}!");
-                    send_text(request, &content, status);
-                    return;
+            let authorization = header.value.as_str().to_owned();
+            let mut login = ApiLogin {
+                request,
+                ip,
+                user,
+                result: Err((500, String::new())),
+                password: None,
+            };
+
+            match authenticate(&authorization, &settings, &tab_list, &mut api_guard) {
+                Ok(ApiAuth::Verified(uuid, scope)) => login.result = Ok((uuid, scope)),
+                Ok(ApiAuth::Password(uuid, password, hash)) => {
+                    login.result = Ok((uuid, ApiScope::Full));
+                    login.password = Some((password, hash));
+
+                    match api_logins.submit(login) {
+                        Some(verified) => login = verified, /* Login Thread Stopped */
+                        None => continue,
+                    }
                 }
+                Err(error) => login.result = Err(error),
             }
-        };
 
-        let Some(user) = settings.users.get(&uuid) else {
-            warn!("[API] {username} tried but isn't whitelisted!");
-            send_text(request, "User isn't whitelisted", 404);
-            return;
-        };
+            let events = (&mut alert_events, &mut request_events);
+            finish_login(login, &mut api_guard, &settings, events);
+        }
+    }
 
-        if user.api_password.is_empty() || user.api_password != password {
-            warn!("[API] {username} tried an incorrect password!");
-            send_text(request, "Incorrect password", 401);
-            return;
+    /// # Panics
+    /// Will panic if `Header::from_str` fails.
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+    pub fn handle_api_request_events(
+        mut request_events: MessageReader<ApiRequestEvent>,
+        mut cmd_events: MessageWriter<CmdEvent>,
+        query: Query<(Entity, &LocalSettings)>,
+        bots: BotQuery,
+        mut api_guard: ResMut<ApiGuard>,
+        mut api_jobs: ResMut<ApiJobs>,
+        mut api_pending: ResMut<ApiPending>,
+        auth_prompts: Res<AuthPrompts>,
+        metrics: Res<Metrics>,
+        settings: Res<GlobalSettings>,
+        stasis_chambers: Res<StasisChambers>,
+        subscribers: Res<EventSubscribers>,
+        tab_list: Res<TabList>,
+    ) {
+        for event in request_events.read() {
+            let Some(mut request) = event
+                .request
+                .lock()
+                .ok()
+                .and_then(|mut request| request.take())
+            else {
+                continue; /* Taken */
+            };
+
+            let url = request.url().to_owned();
+            let (path, query_string) = url.split_once('?').unwrap_or((&url, ""));
+            let Some(segments) = path
+                .trim_start_matches('/')
+                .split('/')
+                .map(|segment| percent_decode(segment, false))
+                .collect::<Option<Vec<_>>>()
+            else {
+                send_error(request, "Invalid URL encoding", 400);
+                continue;
+            };
+
+            let params = parse_query(query_string);
+            let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+            let method = request.method().clone();
+            let route = segments.first().copied().unwrap_or_default();
+            let is_admin = CmdSender::Minecraft(event.uuid).is_admin(&settings);
+            let is_route_allowed = match route {
+                "auth" | "bots" | "chambers" | "config" | "events" | "metrics" | "queue" => {
+                    event.scope.can_read()
+                }
+                "whitelist" if method == Method::Get => event.scope.can_read(),
+                "account" | "whitelist" => event.scope == ApiScope::Full,
+                "pearl" => event.scope != ApiScope::ReadOnly,
+                _ => true, /* Commands are checked by name */
+            };
+
+            if !is_route_allowed {
+                send_error(request, "This token's scope doesn't allow this route", 403);
+                continue;
+            }
+
+            /* Events and metrics cover every player, not only the user's own */
+            let is_admin_route = matches!(route, "auth" | "config" | "events" | "metrics")
+                || (route == "whitelist" && method == Method::Get);
+
+            if matches!(
+                route,
+                "account" | "auth" | "config" | "events" | "whitelist"
+            ) {
+                let action = format!("{method} {path}");
+                let result = if is_admin_route && !is_admin {
+                    "denied"
+                } else {
+                    "allowed"
+                };
+
+                audit(&settings, &event.ip, &event.user, &action, result);
+            }
+
+            if is_admin_route && !is_admin {
+                send_error(request, "You must be an admin to use this route", 403);
+                continue;
+            }
+
+            let reply = match (&method, segments.as_slice()) {
+                (Method::Get, ["auth"]) => to_reply(&*auth_prompts),
+                (Method::Get, ["queue"]) => {
+                    let queue = bots
+                        .iter()
+                        .map(|(profile, _, queue_status)| (profile.name.clone(), queue_status))
+                        .collect::<HashMap<_, _>>();
+
+                    to_reply(&queue)
+                }
+                (Method::Get, ["config", bot_name]) => bots
+                    .iter()
+                    .find(|(profile, _, _)| profile.name.eq_ignore_ascii_case(bot_name))
+                    .map_or_else(
+                        || ApiReply::Error(404, format!("Bot not found: {bot_name}")),
+                        |(_, local_settings, _)| to_reply(local_settings),
+                    ),
+                (Method::Get, ["bots"]) => {
+                    to_reply(&bots.iter().map(ApiBot::from).collect::<Vec<_>>())
+                }
+                (Method::Get, ["bots", bot_name]) => bots
+                    .iter()
+                    .find(|(profile, _, _)| profile.name.eq_ignore_ascii_case(bot_name))
+                    .map_or_else(
+                        || ApiReply::Error(404, format!("Bot not found: {bot_name}")),
+                        |bot| to_reply(&ApiBot::from(bot)),
+                    ),
+                (Method::Get, ["events"]) => {
+                    let split = |key: &str| {
+                        params.get(key).map_or_else(Vec::new, |value| {
+                            value
+                                .split(',')
+                                .filter(|value| !value.is_empty())
+                                .map(str::to_lowercase)
+                                .collect()
+                        })
+                    };
+
+                    ApiReply::Stream(EventFilter {
+                        types: split("types"),
+                        bots:  split("bots"),
+                    })
+                }
+                (Method::Get, ["metrics"]) => {
+                    ApiReply::Text("text/plain; version=0.0.4", metrics.render())
+                }
+                (Method::Get, ["locations"]) => to_reply(&locations(&bots, &stasis_chambers)),
+                (Method::Get, ["chambers"]) => {
+                    /* Admins see every chamber, everyone else only their own */
+                    let owner = match params.get("owner") {
+                        Some(owner) => resolve_player(owner, &tab_list, &mut api_guard).map(Some),
+                        None if is_admin => Ok(None),
+                        None => Ok(Some(event.uuid)),
+                    };
+
+                    match owner {
+                        Err((status, message)) => ApiReply::Error(status, message),
+                        Ok(Some(owner)) if owner != event.uuid && !is_admin => {
+                            ApiReply::Error(403, str!("You can only list your own chambers"))
+                        }
+                        Ok(owner) => to_reply(
+                            &stasis_chambers
+                                .0
+                                .values()
+                                .filter(|chamber| {
+                                    owner.is_none_or(|owner| chamber.owner_uuid == owner)
+                                })
+                                .collect::<Vec<_>>(),
+                        ),
+                    }
+                }
+                (Method::Post, ["pearl"]) => match read_json::<PearlRequest>(&mut request) {
+                    Ok(PearlRequest { location }) => ApiReply::Command(
+                        location
+                            .map_or_else(|| str!("pearl"), |location| format!("pearl {location}")),
+                        ApiReplyKind::Job,
+                    ),
+                    Err(error) => ApiReply::Error(400, error),
+                },
+                (Method::Get, ["whitelist"]) => to_reply(
+                    &settings
+                        .users
+                        .iter()
+                        .map(|(uuid, user)| ApiWhitelistEntry {
+                            uuid:         *uuid,
+                            discord_id:   &user.discord_id,
+                            api_password: !user.api_password.is_empty(),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                (Method::Post | Method::Delete, ["whitelist"]) => {
+                    match read_json::<WhitelistRequest>(&mut request) {
+                        Ok(WhitelistRequest { player }) => {
+                            let action = if method == Method::Post {
+                                "add"
+                            } else {
+                                "remove"
+                            };
+                            ApiReply::Command(
+                                format!("whitelist {action} {player}"),
+                                ApiReplyKind::Json,
+                            )
+                        }
+                        Err(error) => ApiReply::Error(400, error),
+                    }
+                }
+                (Method::Get, ["jobs", id]) => id
+                    .parse()
+                    .ok()
+                    .and_then(|id| api_jobs.get(id))
+                    .filter(|job| job.owner == event.uuid || is_admin)
+                    .map_or_else(|| ApiReply::Error(404, str!("Job not found")), to_reply),
+                (Method::Post, ["account", args @ ..]) => ApiReply::Command(
+                    format!("account add {}", args.join(" ")),
+                    ApiReplyKind::Text,
+                ),
+                (Method::Delete, ["account", username]) if is_valid_username(username) => {
+                    ApiReply::Command(format!("account remove {username}"), ApiReplyKind::Text)
+                }
+                (Method::Delete, ["account", ..]) => {
+                    ApiReply::Error(400, str!("Expected a single valid username"))
+                }
+                (_, ["account", ..]) => {
+                    ApiReply::Error(405, str!("Method not allowed, available: POST, DELETE"))
+                }
+                (_, ["cmd", args @ ..]) => ApiReply::Command(args.join("/"), ApiReplyKind::Text),
+                _ => ApiReply::Error(404, str!("Route not found, see /schema")),
+            };
+
+            let (message, kind) = match reply {
+                ApiReply::Json(status, value) => {
+                    send_value(request, &value, status);
+                    continue;
+                }
+                ApiReply::Error(status, message) => {
+                    send_error(request, &message, status);
+                    continue;
+                }
+                ApiReply::Text(content_type, text) => {
+                    let header = Header::from_bytes("Content-Type", content_type).unwrap();
+                    send_response(request, Response::from_string(text).with_header(header));
+                    continue;
+                }
+                ApiReply::Stream(filter) => {
+                    stream_events(request, filter, &subscribers);
+                    continue;
+                }
+                ApiReply::Command(message, kind) => (message, kind),
+            };
+
+            let prefix = &settings.command_prefix;
+            let cmd = Cmds::parse(&message, prefix).map(|(cmd, _args)| cmd);
+            if cmd.is_some_and(|cmd| !is_cmd_allowed(event.scope, cmd)) {
+                let scope = event.scope.name();
+                let message = format!("The {scope} scope doesn't allow this command");
+                send_error(request, &message, 403);
+                continue;
+            }
+
+            let is_privileged = matches!(
+                cmd,
+                Some(Cmds::Account(_) | Cmds::Config(_) | Cmds::Module(_) | Cmds::Whitelist(_))
+            );
+
+            if is_privileged {
+                audit(&settings, &event.ip, &event.user, &message, "dispatched");
+            }
+
+            let job = (kind == ApiReplyKind::Job).then(|| api_jobs.create(event.uuid, &message));
+            let frontend = ApiFrontend {
+                request: Arc::new(Mutex::new(None)),
+                json:    kind != ApiReplyKind::Text,
+                job:     job.as_ref().map(|job| job.id),
+            };
+
+            let sender = CmdSender::Minecraft(event.uuid);
+            let source = CmdSource::new(frontend.clone());
+            let Some(events) = CmdEvent::dispatch(&message, &settings, sender, source, &query)
+            else {
+                send_error(request, &format!("Unknown command: {message}"), 404);
+                continue;
+            };
+
+            match job {
+                Some(job) => send_value(request, &job, 202),
+                None => {
+                    if let Ok(mut pending) = frontend.request.lock() {
+                        *pending = Some(request);
+                    }
+
+                    let deadline = Instant::now() + settings.http_api.response_timeout;
+                    api_pending.push((frontend.request.clone(), deadline));
+                }
+            }
+
+            cmd_events.write_batch(events);
         }
+    }
 
-        // TODO: Separate the rest into a another handle for routes.
-        let url = request.url().replace("%20", " ");
-        let Some(message) = url.strip_prefix("/cmd/") else {
-            send_text(request, "Invalid route, available: /cmd/<command>", 500);
-            return;
-        };
+    /// Answer requests and finish jobs that no command responded to in time,
+    /// such as a pearl command when no bot has `auto_pearl` enabled.
+    pub fn handle_api_deadlines(
+        mut api_pending: ResMut<ApiPending>,
+        mut api_jobs: ResMut<ApiJobs>,
+        settings: Res<GlobalSettings>,
+    ) {
+        let now = Instant::now();
+        api_pending.retain(|(request, deadline)| {
+            let Ok(mut request) = request.lock() else {
+                return false; /* Poisoned */
+            };
 
-        let mut args = message
-            .split(' ')
-            .map(String::from)
-            .collect::<VecDeque<_>>();
-        let Some(alias) = args.pop_front() else {
-            return; /* Command Missing */
-        };
+            if request.is_none() {
+                return false; /* Answered */
+            }
 
-        let Some(cmd) = Cmds::find(&alias.replace(&settings.command_prefix, "")) else {
-            return; /* Command Invalid */
-        };
+            if *deadline > now {
+                return true; /* Waiting */
+            }
 
-        let mut cmd_event = CmdEvent {
-            args: args.clone(),
-            cmd,
-            entity: None,
-            message: false,
-            sender: CmdSender::ApiServer(uuid),
-            source: CmdSource::ApiServer(Arc::new(Mutex::new(Some(request)))),
-        };
+            if let Some(request) = request.take() {
+                send_error(request, "Timed out waiting for a response", 504);
+            }
 
-        cmd_events.write_batch(std::iter::once(cmd_event.clone()).chain(query.iter().map(
-            |entity| {
-                cmd_event.entity = Some(entity);
-                cmd_event.clone()
-            },
-        )));
-    }
-
-    pub fn handle_send_msg_events(mut msg_events: MessageReader<MsgEvent>) {
-        for event in msg_events.read().cloned() {
-            #[rustfmt::skip]
-            let (
-                CmdSource::ApiServer(request),
-                CmdSender::ApiServer(_)
-            ) = (event.source, event.sender) else {
+            false
+        });
+
+        api_jobs.expire(settings.http_api.response_timeout);
+    }
+
+    pub fn handle_send_msg_events(
+        mut msg_events: MessageReader<MsgEvent>,
+        mut api_jobs: ResMut<ApiJobs>,
+    ) {
+        for event in msg_events.read() {
+            let Some(ApiFrontend { request, json, job }) = event.source.downcast::<ApiFrontend>()
+            else {
                 continue;
             };
 
             info!("[API] [{}] {}", event.status, event.content);
 
+            if let Some(id) = job {
+                api_jobs.complete(*id, event.status, &event.content);
+                continue;
+            }
+
             let Ok(mut request) = request.lock() else {
                 continue; /* Locked */
             };
@@ -179,7 +885,13 @@ impl HttpApiParserPlugin {
                 continue; /* Taken */
             };
 
-            let response = Response::from_string(event.content).with_status_code(event.status);
+            if *json {
+                let reply = json!({ "status": event.status, "message": event.content });
+                send_value(request, &reply, event.status);
+                continue;
+            }
+
+            let response = Response::from_string(&event.content).with_status_code(event.status);
             if let Err(error) = request.respond(response) {
                 error!("[API] Error sending response: {error}");
             }
@@ -187,60 +899,411 @@ impl HttpApiParserPlugin {
     }
 }
 
-pub fn send_text(request: Request, text: &str, code: u16) {
-    let response = Response::from_string(text).with_status_code(code);
+/// A resolved login, Basic passwords are verified later on the login thread.
+enum ApiAuth {
+    Verified(Uuid, ApiScope),
+    /// User, password, and argon2 hash.
+    Password(Uuid, String, String),
+}
+
+/// Resolve the user and scope from Basic credentials or a bearer token.
+fn authenticate(
+    authorization: &str,
+    settings: &GlobalSettings,
+    tab_list: &TabList,
+    api_guard: &mut ApiGuard,
+) -> Result<ApiAuth, (u16, String)> {
+    if let Some(bearer) = authorization.strip_prefix("Bearer ") {
+        let Some(token) = settings.find_api_token(bearer.trim()) else {
+            warn!("[API] Someone tried an invalid token!");
+            return Err((401, str!("Invalid token")));
+        };
+
+        if !settings.users.contains_key(&token.owner) {
+            warn!("[API] {} token owner isn't whitelisted!", token.name);
+            return Err((404, str!("User isn't whitelisted")));
+        }
+
+        return Ok(ApiAuth::Verified(token.owner, token.scope));
+    }
+
+    let encoded = authorization.replace("Basic ", "");
+    let Ok(bytes) = BASE64_STANDARD.decode(&encoded) else {
+        return Err((406, str!("Invalid BASE64")));
+    };
+
+    let Ok(credentials) = String::from_utf8(bytes) else {
+        return Err((406, str!("Invalid UTF-8")));
+    };
+
+    // RFC 2617 provides support for passwords with colons
+    let Some((username, password)) = credentials.split_once(':') else {
+        return Err((406, str!("Invalid Format")));
+    };
+
+    let uuid = if let Some((uuid, _info)) = tab_list
+        .iter()
+        .find(|(_, info)| info.profile.name.to_lowercase() == username.to_lowercase())
+    {
+        *uuid
+    } else {
+        match api_guard.resolve_uuid(&username.to_lowercase()) {
+            Ok(uuid) => uuid,
+            Err((status, content)) => {
+                warn!("[API] {username} tried but {content}!");
+                return Err((status, content));
+            }
+        }
+    };
+
+    let Some(user) = settings.users.get(&uuid) else {
+        warn!("[API] {username} tried but isn't whitelisted!");
+        return Err((404, str!("User isn't whitelisted")));
+    };
+
+    if user.api_password.is_empty() {
+        warn!("[API] {username} tried but has no password!");
+        return Err((401, str!("Incorrect password")));
+    }
+
+    Ok(ApiAuth::Password(
+        uuid,
+        str!(password),
+        user.api_password.clone(),
+    ))
+}
+
+/// Reject a locked out login with how long until it may retry.
+fn send_locked(request: Request, remaining: Duration) {
+    let seconds = remaining.as_secs() + 1;
+    let header = Header::from_str(&format!("Retry-After: {seconds}")).unwrap();
+    let response =
+        error_response("Too many failed logins, try again later", 429).with_header(header);
+
     send_response(request, response);
 }
 
-pub fn send_response<R: Read>(request: Request, response: Response<R>) {
-    if let Err(error) = request.respond(response) {
-        error!("[API] Error sending response: {error}");
+/// Count the login result for lockouts and the audit log, then route or reject the request.
+fn finish_login(
+    login: ApiLogin,
+    api_guard: &mut ApiGuard,
+    settings: &GlobalSettings,
+    (alert_events, request_events): (
+        &mut MessageWriter<ApiAlertEvent>,
+        &mut MessageWriter<ApiRequestEvent>,
+    ),
+) {
+    let ApiLogin {
+        request,
+        ip,
+        user,
+        result,
+        ..
+    } = login;
+
+    /* Logins queued before a lockout must not get past it */
+    let keys = [format!("ip:{ip}"), format!("user:{user}")];
+    if let Some(remaining) = api_guard.locked_for(&keys) {
+        audit(settings, &ip, &user, "login", "locked");
+        send_locked(request, remaining);
+        return;
     }
+
+    let (uuid, scope) = match result {
+        Ok(auth) => {
+            /* IP failures only expire, a valid login between guesses can't reset them */
+            api_guard.succeed(&keys[1]);
+            audit(settings, &ip, &user, "login", "success");
+            auth
+        }
+        Err((status, content)) => {
+            audit(settings, &ip, &user, "login", "failure");
+            for key in keys {
+                let Some((failures, lockout)) = api_guard.fail(&key, &settings.http_api) else {
+                    continue;
+                };
+
+                let seconds = lockout.as_secs();
+                warn!("[API] {key} locked out for {seconds}s after {failures} failures");
+                alert_events.write(ApiAlertEvent {
+                    key,
+                    failures,
+                    lockout,
+                });
+            }
+
+            send_error(request, &content, status);
+            return;
+        }
+    };
+
+    request_events.write(ApiRequestEvent {
+        request: Arc::new(Mutex::new(Some(request))),
+        uuid,
+        scope,
+        ip,
+        user,
+    });
 }
 
-const MOJANG_URL: &str = "https://api.mojang.com/users/profiles/minecraft";
+/// The username or token id a login claims to be, used for lockouts and the audit log.
+fn auth_identity(authorization: &str) -> String {
+    if let Some(bearer) = authorization.strip_prefix("Bearer ") {
+        let id = bearer
+            .trim()
+            .strip_prefix("sbt_")
+            .and_then(|token| token.split('_').next());
+        return format!("token:{}", id.unwrap_or_default());
+    }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct Mojang200 {
-    #[serde(rename = "id")]
-    pub uuid: String,
-    pub name: String,
+    BASE64_STANDARD
+        .decode(authorization.replace("Basic ", ""))
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|credentials| Some(credentials.split_once(':')?.0.to_lowercase()))
+        .unwrap_or_default()
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct Mojang404 {
-    #[serde(rename = "errorMessage")]
-    pub error: String,
-    pub path:  String,
+/// Append an entry to the API audit log, if enabled.
+fn audit(settings: &GlobalSettings, ip: &str, user: &str, action: &str, result: &str) {
+    let file_name = &settings.http_api.audit_log;
+    if file_name.is_empty() {
+        return; /* Audit Log Disabled */
+    }
+
+    let entry = ApiAuditEntry {
+        timestamp: unix_timestamp(),
+        ip,
+        user,
+        action,
+        result,
+    };
+
+    let write = || -> anyhow::Result<()> {
+        let path = GlobalSettings::path()?.with_file_name(file_name);
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    };
+
+    if let Err(error) = write() {
+        error!("[API] Failed to write audit log: {error}");
+    }
 }
 
-/// Fetch a Minecraft UUID from a Username
-///
-/// # Errors
-/// Will return `Err` if `ureq::get`, `Body::read_json`, `Uuid::parse_str`, or Mojang fails.
-pub fn fetch_uuid(username: &str) -> Result<Uuid, (u16, String)> {
-    let url = format!("{MOJANG_URL}/{username}");
-    let response = ureq::get(&url)
-        .call()
-        .map_err(|_| (500, "Mojang request failed.".to_string()))?;
+/// Whether a token scope can run a command.
+const fn is_cmd_allowed(scope: ApiScope, cmd: Cmds) -> bool {
+    match scope {
+        ApiScope::Full => true,
+        ApiScope::Pearl => matches!(cmd, Cmds::Pearl(_)),
+        ApiScope::ReadOnly => matches!(
+            cmd,
+            Cmds::Playtime(_) | Cmds::Queue(_) | Cmds::Seen(_) | Cmds::Status(_)
+        ),
+    }
+}
+
+/// Resolve a player from a UUID or username.
+fn resolve_player(
+    player: &str,
+    tab_list: &TabList,
+    api_guard: &mut ApiGuard,
+) -> Result<Uuid, (u16, String)> {
+    if let Ok(uuid) = Uuid::parse_str(player) {
+        return Ok(uuid);
+    }
+
+    tab_list
+        .iter()
+        .find(|(_, info)| info.profile.name.eq_ignore_ascii_case(player))
+        .map_or_else(
+            || api_guard.resolve_uuid(&player.to_lowercase()),
+            |(uuid, _)| Ok(*uuid),
+        )
+}
+
+/// Every pearl location with its bots and number of chambers.
+fn locations(bots: &BotQuery, stasis_chambers: &StasisChambers) -> Vec<ApiLocation> {
+    let mut locations = Vec::<ApiLocation>::new();
+    for (profile, local_settings, _) in bots {
+        let server = &local_settings.server;
+        let location = &local_settings.auto_pearl.location;
+        if let Some(api_location) = locations.iter_mut().find(|api_location| {
+            &api_location.server == server && &api_location.location == location
+        }) {
+            api_location.bots.push(profile.name.clone());
+            continue;
+        }
+
+        locations.push(ApiLocation {
+            server:   server.clone(),
+            location: location.clone(),
+            bots:     vec![profile.name.clone()],
+            chambers: stasis_chambers
+                .0
+                .values()
+                .filter(|chamber| chamber.is_at(server, location))
+                .count(),
+        });
+    }
 
-    let status = response.status();
-    let mut body = response.into_body();
+    locations
+}
 
-    match status.as_u16() {
-        200 => {
-            let resp = body
-                .read_json::<Mojang200>()
-                .map_err(|_| (500, "Mojang parsing json failed.".to_string()))?;
-            Uuid::parse_str(&resp.uuid)
-                .map_err(|_| (500, format!("Mojang parsing uuid failed: {}", resp.uuid)))
+/// Decode a percent-encoded URL component, `+` is a space in query strings.
+fn percent_decode(text: &str, plus_as_space: bool) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut iter = text.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            b'+' if plus_as_space => bytes.push(b' '),
+            byte => bytes.push(byte),
         }
-        404 => {
-            let resp = body
-                .read_json::<Mojang404>()
-                .map_err(|_| (500, "Mojang parsing json failed.".to_string()))?;
-            Err((404, resp.error))
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Some((percent_decode(key, true)?, percent_decode(value, true)?))
+        })
+        .collect()
+}
+
+/// Read a JSON request body, an empty body is read as `{}`.
+fn read_json<T: DeserializeOwned>(request: &mut Request) -> Result<T, String> {
+    let mut body = String::new();
+    if let Err(error) = request.as_reader().read_to_string(&mut body) {
+        return Err(format!("Invalid request body: {error}"));
+    }
+
+    let body = if body.trim().is_empty() { "{}" } else { &body };
+    serde_json::from_str(body).map_err(|error| format!("Invalid JSON body: {error}"))
+}
+
+fn to_reply<T: Serialize + ?Sized>(value: &T) -> ApiReply {
+    match serde_json::to_value(value) {
+        Ok(value) => ApiReply::Json(200, value),
+        Err(error) => ApiReply::Error(500, error.to_string()),
+    }
+}
+
+/// Hand the connection to a thread streaming matching events as Server-Sent Events,
+/// the subscriber is removed once the client disconnects.
+fn stream_events(request: Request, filter: EventFilter, subscribers: &EventSubscribers) {
+    const HEADERS: &str = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                           Cache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+
+    let mut writer = request.into_writer();
+    if writer
+        .write_all(HEADERS.as_bytes())
+        .and_then(|()| writer.flush())
+        .is_err()
+    {
+        return; /* Client Disconnected */
+    }
+
+    let (tx, rx) = mpsc::channel::<String>();
+    let id = subscribers.subscribe(filter, tx);
+    let stream_subscribers = subscribers.clone();
+
+    let spawn_result = std::thread::Builder::new()
+        .name(str!("api-events"))
+        .spawn(move || {
+            loop {
+                let chunk = match rx.recv_timeout(EVENT_KEEP_ALIVE) {
+                    Ok(json) => format!("data: {json}\n\n"),
+                    Err(mpsc::RecvTimeoutError::Timeout) => str!(": keep-alive\n\n"),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                };
+
+                if writer
+                    .write_all(chunk.as_bytes())
+                    .and_then(|()| writer.flush())
+                    .is_err()
+                {
+                    break; /* Client Disconnected */
+                }
+            }
+
+            stream_subscribers.unsubscribe(&[id]);
+        });
+
+    if let Err(error) = spawn_result {
+        error!("[API] Failed to spawn event stream: {error}");
+        subscribers.unsubscribe(&[id]);
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub fn send_text(request: Request, text: &str, code: u16) {
+    let response = Response::from_string(text).with_status_code(code);
+    send_response(request, response);
+}
+
+/// # Panics
+/// Will panic if `Header::from_str` fails.
+pub fn send_json(request: Request, json: String) {
+    let header = Header::from_str("Content-Type: application/json").unwrap();
+    let response = Response::from_string(json).with_header(header);
+    send_response(request, response);
+}
+
+/// # Panics
+/// Will panic if `Header::from_str` fails.
+pub fn send_value<T: Serialize + ?Sized>(request: Request, value: &T, code: u16) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            let header = Header::from_str("Content-Type: application/json").unwrap();
+            let response = Response::from_string(json)
+                .with_status_code(code)
+                .with_header(header);
+
+            send_response(request, response);
         }
-        code => Err((code, format!("Mojang Error: {code}"))),
+        Err(error) => send_error(request, &error.to_string(), 500),
+    }
+}
+
+/// Send a typed JSON error, `error` is the snake case reason phrase. (`not_found`)
+pub fn send_error(request: Request, message: &str, code: u16) {
+    send_response(request, error_response(message, code));
+}
+
+/// # Panics
+/// Will panic if `Header::from_str` fails.
+#[must_use]
+pub fn error_response(message: &str, code: u16) -> Response<Cursor<Vec<u8>>> {
+    let error = StatusCode(code)
+        .default_reason_phrase()
+        .to_lowercase()
+        .replace([' ', '-'], "_");
+    let json = json!({ "error": error, "status": code, "message": message });
+    let header = Header::from_str("Content-Type: application/json").unwrap();
+
+    Response::from_string(json.to_string())
+        .with_status_code(code)
+        .with_header(header)
+}
+
+pub fn send_response<R: Read>(request: Request, response: Response<R>) {
+    if let Err(error) = request.respond(response) {
+        error!("[API] Error sending response: {error}");
     }
 }
@@ -2,7 +2,7 @@ use std::{
     collections::VecDeque,
     fmt::Write,
     sync::LazyLock,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Result;
@@ -37,6 +37,25 @@ use crate::prelude::*;
 /// Minecraft chat command parsing integration
 pub struct MinecraftParserPlugin;
 
+/// Minecraft chat [`CommandFrontend`], responses are whispered back to the sender.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinecraftFrontend {
+    /// Encryption the command was received with, responses are encrypted the same way.
+    pub encryption: Option<EncryptionType>,
+    /// Outbound chat priority of responses.
+    pub priority:   ChatPriority,
+}
+
+impl CommandFrontend for MinecraftFrontend {
+    fn name(&self) -> &'static str {
+        "minecraft"
+    }
+
+    fn in_game(&self) -> bool {
+        true
+    }
+}
+
 impl Plugin for MinecraftParserPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(CmdCooldown::default())
@@ -46,7 +65,8 @@ impl Plugin for MinecraftParserPlugin {
                 Update,
                 (
                     Self::handle_chat_received_events,
-                    Self::handle_send_msg_events.before(handle_send_chat_event),
+                    Self::handle_send_msg_events,
+                    Self::handle_chat_outbox.before(handle_send_chat_event),
                 )
                     .chain(),
             );
@@ -58,54 +78,50 @@ impl MinecraftParserPlugin {
         mut chat_received_events: MessageReader<ChatReceivedEvent>,
         mut cmd_events: MessageWriter<CmdEvent>,
         mut cooldown: ResMut<CmdCooldown>,
-        query: Query<&TabList>,
+        query: Query<(&TabList, &LocalSettings)>,
         settings: Res<GlobalSettings>,
     ) {
         let mut events = Vec::new();
         for event in chat_received_events.read() {
+            let Ok((tab_list, local_settings)) = query.get(event.entity) else {
+                return; /* Not Connected */
+            };
+
+            let profile = settings.server_profile(&local_settings.server);
+            let chat_profile = settings.chat_profile(&profile.chat_profile);
             let (username, content) = event.packet.split_sender_and_content();
             let (username, content, message) = if let Some(username) = username {
                 (username, content, event.packet.is_whisper()) /* Vanilla Server Format */
-            } else if let Some((_whole, username, whisper, content)) = regex_captures!(
-                r"^(?:\[.+\] )?([a-zA-Z_0-9]{1,16}) (?:> )?(whispers: |-> me] )?(.+)$",
-                &content /* Custom Server Formats */
-            ) {
-                (str!(username), str!(content), !whisper.is_empty())
+            } else if let Some(captures) = parse_chat_profile(&chat_profile, &content) {
+                captures /* Custom Server Formats */
             } else {
                 continue;
             };
 
-            let Ok(tab_list) = query.get(event.entity) else {
-                return; /* Not Connected */
-            };
-
             let Some((uuid, _)) = tab_list.iter().find(|(_, i)| i.profile.name == username) else {
                 continue; /* Not Online */
             };
 
-            if settings.whitelist_only && !settings.users.contains_key(uuid) {
-                continue; /* Not Whitelisted */
-            }
-
             let key = AesKey::decode_base64(&settings.chat.key).unwrap_or_else(|_| KEY.clone());
             let (encryption, content) = find_encryption(&content, &key);
-            let mut args = content
-                .split(' ')
-                .map(String::from)
-                .collect::<VecDeque<_>>();
-
-            let Some(alias) = args.pop_front() else {
-                continue; /* Command Missing */
-            };
-
-            if !alias.starts_with(&settings.command_prefix) {
+            if !content.starts_with(&settings.command_prefix) {
                 continue; /* Command Invalid */
             }
 
-            let Some(command) = Cmds::find(&alias.replace(&settings.command_prefix, "")) else {
+            let Some((command, args)) = Cmds::parse(&content, &settings.command_prefix) else {
                 continue; /* Command Invalid */
             };
 
+            /* Players link from Discord before they're whitelisted, so they can always verify */
+            let sender = CmdSender::Minecraft(*uuid);
+            if !sender.is_whitelisted(&settings) && !matches!(command, Cmds::Verify(_)) {
+                continue; /* Not Whitelisted */
+            }
+
+            if !sender.is_whitelisted_on(&settings, &profile) {
+                continue; /* Not Whitelisted On This Server */
+            }
+
             if cooldown.check(&username, settings.command_cooldown) {
                 info!("Command on cooldown");
                 continue; /* Command Cooldown */
@@ -116,8 +132,11 @@ impl MinecraftParserPlugin {
                 cmd: command,
                 entity: Some(event.entity),
                 message,
-                sender: CmdSender::Minecraft(*uuid),
-                source: CmdSource::Minecraft(encryption),
+                sender,
+                source: CmdSource::new(MinecraftFrontend {
+                    encryption,
+                    ..Default::default()
+                }),
             });
         }
 
@@ -125,22 +144,21 @@ impl MinecraftParserPlugin {
     }
 
     pub fn handle_send_msg_events(
-        mut chat_kind_events: MessageWriter<SendChatKindEvent>,
         mut msg_events: MessageReader<MsgEvent>,
-        query: Query<(&TabList, &LocalSettings)>,
+        mut query: Query<(&TabList, &LocalSettings, &mut ChatOutbox)>,
         settings: Res<GlobalSettings>,
     ) {
-        for mut event in msg_events.read().cloned() {
-            #[rustfmt::skip]
-            let (
-                Some(entity),
-                CmdSource::Minecraft(type_encryption),
-                CmdSender::Minecraft(uuid)
-            ) = (event.entity, event.source, event.sender) else {
+        for event in msg_events.read() {
+            let frontend = event.source.downcast::<MinecraftFrontend>();
+            let Some(&MinecraftFrontend { encryption, priority }) = frontend else {
+                continue;
+            };
+
+            let (Some(entity), CmdSender::Minecraft(uuid)) = (event.entity, event.sender) else {
                 continue;
             };
 
-            let Ok((tab_list, local_settings)) = query.get(entity) else {
+            let Ok((tab_list, local_settings, mut chat_outbox)) = query.get_mut(entity) else {
                 return;
             };
 
@@ -157,22 +175,204 @@ impl MinecraftParserPlugin {
             };
 
             info!("Command Response: {}", event.content);
-            if local_settings.anti_spam.enabled
-                && let Ok(duration) = SystemTime::now().duration_since(UNIX_EPOCH)
+            let profile = settings.server_profile(&local_settings.server);
+            let chat_profile = settings.chat_profile(&profile.chat_profile);
+            let template = chat_profile.whisper_template.trim_start_matches('/');
+            let anti_spam = &local_settings.anti_spam;
+            let finish = |chunk: &str| {
+                let mut content = str!(chunk);
+                if anti_spam.enabled {
+                    if anti_spam.unix_epoch
+                        && let Ok(duration) = SystemTime::now().duration_since(UNIX_EPOCH)
+                    {
+                        let _ = write!(content, " [{}]", duration.as_secs());
+                    }
+
+                    if anti_spam.random_suffix > 0 {
+                        let suffix = (0..anti_spam.random_suffix)
+                            .map(|_| fastrand::alphanumeric())
+                            .collect::<String>();
+
+                        let _ = write!(content, " {suffix}");
+                    }
+                }
+
+                try_encrypt(&mut content, &settings.chat, encryption);
+                template
+                    .replace("{username}", &username)
+                    .replace("{message}", &content)
+            };
+
+            /* Shrink the chunks until the prefix, suffix, and encryption fit the server limit */
+            let mut limit = chat_profile.max_length;
+            let chunks = loop {
+                let chunks = split_message(&event.content, limit)
+                    .into_iter()
+                    .map(|chunk| (finish(&chunk), chunk))
+                    .collect::<Vec<_>>();
+
+                let longest = chunks
+                    .iter()
+                    .map(|(command, _)| command.chars().count())
+                    .max()
+                    .unwrap_or(0);
+
+                if longest <= chat_profile.max_length || limit <= MIN_CHUNK_LENGTH {
+                    break chunks;
+                }
+
+                limit = limit
+                    .saturating_sub(longest - chat_profile.max_length)
+                    .max(MIN_CHUNK_LENGTH);
+            };
+
+            let encrypted = settings.chat.mode != EncryptionMode::Never
+                && (encryption.is_some() || settings.chat.mode == EncryptionMode::Always);
+
+            for (mut command, chunk) in chunks {
+                if let Some((index, _)) = command.char_indices().nth(chat_profile.max_length) {
+                    if encrypted {
+                        warn!("Encrypted chunk exceeds the server limit, dropped: {chunk}");
+                        continue; /* Truncated ciphertext can't be decrypted */
+                    }
+
+                    command.truncate(index); /* Server Limit */
+                }
+
+                chat_outbox.push(
+                    OutgoingChat {
+                        content:  command,
+                        key:      format!("{username} {chunk}"),
+                        kind:     ChatKind::Command,
+                        priority,
+                    },
+                    anti_spam,
+                );
+            }
+        }
+    }
+
+    pub fn handle_chat_outbox(
+        mut chat_kind_events: MessageWriter<SendChatKindEvent>,
+        mut query: Query<(Entity, &LocalSettings, &mut ChatOutbox)>,
+        settings: Res<GlobalSettings>,
+    ) {
+        for (entity, local_settings, mut chat_outbox) in &mut query {
+            let profile = settings.server_profile(&local_settings.server);
+            let chat_profile = settings.chat_profile(&profile.chat_profile);
+            if chat_outbox
+                .last_sent
+                .is_some_and(|instant| instant.elapsed() < chat_profile.send_delay)
             {
-                let _ = write!(event.content, " [{}]", duration.as_secs());
+                continue; /* Rate Limited */
             }
 
-            try_encrypt(&mut event.content, &settings.chat, type_encryption);
+            let window = local_settings.anti_spam.duplicate_window;
+            let Some(chat) = chat_outbox.pop(window) else {
+                continue;
+            };
+
             chat_kind_events.write(SendChatKindEvent {
-                content: format!("w {username} {}", event.content),
+                content: chat.content,
                 entity,
-                kind: ChatKind::Command,
+                kind: chat.kind,
             });
         }
     }
 }
 
+/// Smallest chunk a response is split into before truncating, encrypted chunks are dropped.
+const MIN_CHUNK_LENGTH: usize = 32;
+
+/// Outbound chat priority, higher priorities are sent first.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ChatPriority {
+    /// Unprompted messages such as pearl limit warnings.
+    Notification,
+    /// Responses to a player's command.
+    #[default]
+    Reply,
+    /// Proxy control commands such as `ZenithProxy`.
+    Control,
+}
+
+/// A chat message or command waiting in a bot's [`ChatOutbox`].
+#[derive(Debug)]
+pub struct OutgoingChat {
+    pub content:  String,
+    /// Content before anti-spam suffixes and encryption, used to suppress duplicates.
+    pub key:      String,
+    pub kind:     ChatKind,
+    pub priority: ChatPriority,
+}
+
+/// Per-bot outbound chat queue, sent at the chat profile's send delay.
+#[derive(Component, Debug, Default)]
+pub struct ChatOutbox {
+    queue:     VecDeque<OutgoingChat>,
+    recent:    HashMap<String, Instant>,
+    last_sent: Option<Instant>,
+}
+
+impl ChatOutbox {
+    /// Queue a chat message, duplicates of a queued or recently sent message are dropped.
+    /// Recently sent messages are allowed again when a suffix makes them unique,
+    /// and control messages are never dropped, their senders have their own cooldowns.
+    pub fn push(&mut self, chat: OutgoingChat, anti_spam: &AntiSpam) {
+        let window = anti_spam.duplicate_window;
+        let has_suffix = anti_spam.unix_epoch || anti_spam.random_suffix > 0;
+        let is_recent = !has_suffix
+            && self
+                .recent
+                .get(&chat.key)
+                .is_some_and(|instant| instant.elapsed() < window);
+
+        let is_queued = self.queue.iter().any(|queued| queued.key == chat.key);
+        if anti_spam.enabled && chat.priority != ChatPriority::Control && (is_recent || is_queued) {
+            debug!("Duplicate Chat Dropped: {}", chat.key);
+            return;
+        }
+
+        if anti_spam.max_queue > 0 && self.queue.len() >= anti_spam.max_queue {
+            warn!("Chat Queue Full, Dropped: {}", chat.key);
+            return;
+        }
+
+        self.queue.push_back(chat);
+    }
+
+    /// Take the oldest message with the highest priority.
+    pub fn pop(&mut self, window: Duration) -> Option<OutgoingChat> {
+        let priority = self.queue.iter().map(|chat| chat.priority).max()?;
+        let index = self.queue.iter().position(|chat| chat.priority == priority)?;
+        let chat = self.queue.remove(index)?;
+        let now = Instant::now();
+
+        self.recent.retain(|_, instant| now.duration_since(*instant) < window);
+        self.recent.insert(chat.key.clone(), now);
+        self.last_sent = Some(now);
+
+        Some(chat)
+    }
+}
+
+/// Parse a chat message with the chat profile's whisper and public chat regexes.
+pub fn parse_chat_profile(
+    chat_profile: &ChatProfile,
+    content: &str,
+) -> Option<(String, String, bool)> {
+    [(&chat_profile.whisper, true), (&chat_profile.public, false)]
+        .into_iter()
+        .filter(|(regex, _)| !regex.as_str().is_empty())
+        .find_map(|(regex, whisper)| {
+            let captures = regex.captures(content)?;
+            let username = captures.name("username")?.as_str();
+            let content = captures.name("content")?.as_str();
+
+            Some((str!(username), str!(content), whisper))
+        })
+}
+
 /* No Chat Reports Mod */
 
 pub static KEY: LazyLock<AesKey> = LazyLock::new(|| {
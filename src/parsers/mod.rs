@@ -1,39 +1,49 @@
+pub mod console;
+#[cfg(unix)]
+pub mod control;
 #[cfg(feature = "bot")]
 pub mod discord;
 #[cfg(feature = "api")]
 pub mod http_api;
 pub mod minecraft;
 
-#[cfg(feature = "api")]
-use std::sync::Mutex;
 use std::{
+    any::Any,
     collections::{HashMap, VecDeque},
+    fmt::{self, Debug, Display, Formatter},
     time::{Duration, Instant},
 };
 
 use azalea::{ecs::prelude::*, prelude::*};
-#[cfg(feature = "bot")]
-use serenity::all::{ChannelId, UserId};
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-#[cfg(feature = "api")]
-use tiny_http::Request;
 use uuid::Uuid;
 
 use crate::prelude::*;
 
 pub trait Cmd {
     fn aliases(&self) -> Vec<&'static str>;
+
+    /// Short description shown in Discord slash commands.
+    fn description(&self) -> &'static str;
 }
 
 /// Compile time checked list of commands
 #[derive(Clone, Copy, Debug, Eq, PartialEq, EnumIter)]
 pub enum Cmds {
+    Account(AccountCommandPlugin),
+    Config(ConfigCommandPlugin),
     Interact(InteractCommandPlugin),
     Join(JoinCommandPlugin),
     Leave(LeaveCommandPlugin),
+    Module(ModuleCommandPlugin),
+    Notify(NotifyCommandPlugin),
     Pearl(PearlCommandPlugin),
     Playtime(PlaytimeCommandPlugin),
+    Queue(QueueCommandPlugin),
     Seen(SeenCommandPlugin),
+    Status(StatusCommandPlugin),
+    Verify(VerifyCommandPlugin),
     Whitelist(WhitelistCommandPlugin),
 }
 
@@ -41,33 +51,172 @@ impl Cmds {
     #[must_use]
     pub fn find(alias: &str) -> Option<Self> {
         Self::iter().find(|cmds| match cmds {
+            Self::Account(cmd) => cmd.aliases().contains(&alias),
+            Self::Config(cmd) => cmd.aliases().contains(&alias),
             Self::Interact(cmd) => cmd.aliases().contains(&alias),
             Self::Join(cmd) => cmd.aliases().contains(&alias),
             Self::Leave(cmd) => cmd.aliases().contains(&alias),
+            Self::Module(cmd) => cmd.aliases().contains(&alias),
+            Self::Notify(cmd) => cmd.aliases().contains(&alias),
             Self::Pearl(cmd) => cmd.aliases().contains(&alias),
             Self::Playtime(cmd) => cmd.aliases().contains(&alias),
+            Self::Queue(cmd) => cmd.aliases().contains(&alias),
             Self::Seen(cmd) => cmd.aliases().contains(&alias),
+            Self::Status(cmd) => cmd.aliases().contains(&alias),
+            Self::Verify(cmd) => cmd.aliases().contains(&alias),
             Self::Whitelist(cmd) => cmd.aliases().contains(&alias),
         })
     }
+
+    /// Get the command plugin behind this variant.
+    #[must_use]
+    pub fn cmd(&self) -> &dyn Cmd {
+        match self {
+            Self::Account(cmd) => cmd,
+            Self::Config(cmd) => cmd,
+            Self::Interact(cmd) => cmd,
+            Self::Join(cmd) => cmd,
+            Self::Leave(cmd) => cmd,
+            Self::Module(cmd) => cmd,
+            Self::Notify(cmd) => cmd,
+            Self::Pearl(cmd) => cmd,
+            Self::Playtime(cmd) => cmd,
+            Self::Queue(cmd) => cmd,
+            Self::Seen(cmd) => cmd,
+            Self::Status(cmd) => cmd,
+            Self::Verify(cmd) => cmd,
+            Self::Whitelist(cmd) => cmd,
+        }
+    }
+
+    /// Split a message into a command and its arguments, stripping the command prefix.
+    #[must_use]
+    pub fn parse(message: &str, prefix: &str) -> Option<(Self, VecDeque<String>)> {
+        let mut args = message
+            .split(' ')
+            .map(String::from)
+            .collect::<VecDeque<_>>();
+
+        let alias = args.pop_front()?;
+        let cmd = Self::find(&alias.replace(prefix, ""))?;
+
+        Some((cmd, args))
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// A transport that commands arrive from and responses are delivered through.
+///
+/// Frontends turn inbound messages into [`CmdEvent`]s with a resolved [`CmdSender`],
+/// attaching themselves as the [`CmdSource`].
+///
+/// Delivery isn't part of this trait because every transport needs its own ECS state,
+/// such as the bot's `ChatOutbox`, the Discord HTTP client, or the API job table.
+/// Each frontend's plugin instead runs a `handle_send_msg_events` system that
+/// delivers the [`MsgEvent`]s whose source downcasts back to its own type, and
+/// ignores the rest, so a new frontend must add that system alongside its parser.
+pub trait CommandFrontend: Any + Debug + Send + Sync {
+    /// Short name used for logging and attribution.
+    fn name(&self) -> &'static str;
+
+    /// Whether the command was sent through in-game chat.
+    fn in_game(&self) -> bool {
+        false
+    }
+
+    /// Whether responses can ask for a confirmation, such as with Discord buttons.
+    fn interactive(&self) -> bool {
+        false
+    }
+}
+
+/// The identity a frontend resolved for the sender of a command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CmdSender {
-    #[cfg(feature = "api")]
-    ApiServer(Uuid),
-    #[cfg(feature = "bot")]
-    Discord(UserId),
+    /// Local operator on stdin, bypasses whitelist checks.
+    Console,
+    /// Discord user id, which may or may not be linked to a Minecraft account.
+    Discord(u64),
+    /// Minecraft account, authenticated in-game or through the API.
     Minecraft(Uuid),
 }
 
-#[derive(Clone, Debug)]
-pub enum CmdSource {
-    #[cfg(feature = "api")]
-    ApiServer(Arc<Mutex<Option<Request>>>),
-    #[cfg(feature = "bot")]
-    Discord(ChannelId),
-    Minecraft(Option<EncryptionType>),
+impl CmdSender {
+    /// Check if the sender may run admin commands, the console always can.
+    #[must_use]
+    pub fn is_admin(&self, settings: &GlobalSettings) -> bool {
+        match self {
+            Self::Console => true,
+            Self::Discord(user_id) => settings.users.iter().any(|(uuid, user)| {
+                user.discord_id == user_id.to_string() && settings.admins.contains(uuid)
+            }),
+            Self::Minecraft(uuid) => settings.admins.contains(uuid),
+        }
+    }
+
+    /// Check if the sender may use the bots of a server profile, empty whitelists allow everyone.
+    #[must_use]
+    pub fn is_whitelisted_on(&self, settings: &GlobalSettings, profile: &ServerProfile) -> bool {
+        if profile.whitelist.is_empty() || self.is_admin(settings) {
+            return true;
+        }
+
+        match self {
+            Self::Console => true,
+            Self::Discord(user_id) => settings.users.iter().any(|(uuid, user)| {
+                user.discord_id == user_id.to_string() && profile.whitelist.contains(uuid)
+            }),
+            Self::Minecraft(uuid) => profile.whitelist.contains(uuid),
+        }
+    }
+
+    /// Check if the sender may run commands when `whitelist_only` is enabled.
+    #[must_use]
+    pub fn is_whitelisted(&self, settings: &GlobalSettings) -> bool {
+        if !settings.whitelist_only {
+            return true;
+        }
+
+        match self {
+            Self::Console => true,
+            Self::Discord(user_id) => settings
+                .users
+                .values()
+                .any(|user| user.discord_id == user_id.to_string()),
+            Self::Minecraft(uuid) => settings.users.contains_key(uuid),
+        }
+    }
+}
+
+impl Display for CmdSender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Console => write!(f, "operator"),
+            Self::Discord(user_id) => write!(f, "{user_id}"),
+            Self::Minecraft(uuid) => write!(f, "{uuid}"),
+        }
+    }
+}
+
+/// Type erased [`CommandFrontend`] that a command was received from.
+#[derive(Clone, Debug, Deref)]
+pub struct CmdSource(Arc<dyn CommandFrontend>);
+
+impl CmdSource {
+    pub fn new(frontend: impl CommandFrontend) -> Self {
+        Self(Arc::new(frontend))
+    }
+
+    /// Get the concrete frontend if this source belongs to it.
+    #[must_use]
+    pub fn downcast<T: CommandFrontend>(&self) -> Option<&T> {
+        (self.0.as_ref() as &dyn Any).downcast_ref::<T>()
+    }
+
+    /// Whether both sources belong to the same received command.
+    #[must_use]
+    pub fn is_same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 #[derive(Clone, Debug, Message)]
@@ -81,6 +230,58 @@ pub struct CmdEvent {
     pub source:  CmdSource,
 }
 
+impl CmdEvent {
+    /// Parse a remote command and broadcast it to every bot, as the API and control socket do.
+    pub fn dispatch<'a>(
+        message: &str,
+        settings: &GlobalSettings,
+        sender: CmdSender,
+        source: CmdSource,
+        bots: impl IntoIterator<Item = (Entity, &'a LocalSettings)>,
+    ) -> Option<Vec<Self>> {
+        let (cmd, args) = Cmds::parse(message, &settings.command_prefix)?;
+        let cmd_event = Self {
+            args,
+            cmd,
+            entity: None,
+            message: false,
+            sender,
+            source,
+        };
+
+        Some(cmd_event.broadcast(bots, settings))
+    }
+
+    /// Whether this is the copy of a broadcast command that should answer swarm-wide requests.
+    #[must_use]
+    pub fn is_primary(&self) -> bool {
+        self.entity.is_none() || self.source.in_game()
+    }
+
+    /// Duplicate the event for each bot whose server profile whitelists the sender,
+    /// plus once without an entity for swarm-wide commands.
+    pub fn broadcast<'a>(
+        self,
+        bots: impl IntoIterator<Item = (Entity, &'a LocalSettings)>,
+        settings: &GlobalSettings,
+    ) -> Vec<Self> {
+        let mut events = vec![self.clone()];
+        events.extend(
+            bots.into_iter()
+                .filter(|(_, local_settings)| {
+                    let profile = settings.server_profile(&local_settings.server);
+                    self.sender.is_whitelisted_on(settings, &profile)
+                })
+                .map(|(entity, _)| Self {
+                    entity: Some(entity),
+                    ..self.clone()
+                }),
+        );
+
+        events
+    }
+}
+
 #[derive(Clone, Debug, Message)]
 pub struct MsgEvent {
     pub content: String,
@@ -90,6 +291,63 @@ pub struct MsgEvent {
     pub status:  u16,
 }
 
+/// Split a message into chunks of at most `limit` characters, preferring line and word boundaries.
+#[must_use]
+pub fn split_message(content: &str, limit: usize) -> Vec<String> {
+    let limit = limit.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = content.trim();
+
+    while rest.chars().count() > limit {
+        let end = rest.char_indices().nth(limit).map_or(rest.len(), |(index, _)| index);
+        let head = &rest[..end];
+        let split = if rest[end..].starts_with(char::is_whitespace) {
+            end /* Boundary At Limit */
+        } else {
+            head.rfind('\n')
+                .filter(|&index| index > head.len() / 2)
+                .or_else(|| head.rfind(char::is_whitespace))
+                .filter(|&index| index > 0)
+                .unwrap_or(end) /* No Boundary, Split Word */
+        };
+
+        chunks.push(str!(rest[..split].trim_end()));
+        rest = rest[split..].trim_start();
+    }
+
+    if !rest.is_empty() {
+        chunks.push(str!(rest));
+    }
+
+    chunks
+}
+
+/// Format one page of a list, pages start at one and out of range pages are clamped.
+#[must_use]
+pub fn paginate<T: Display>(
+    items: &[T],
+    separator: &str,
+    page: usize,
+    page_size: usize,
+) -> String {
+    let page_size = page_size.max(1);
+    let pages = items.len().div_ceil(page_size).max(1);
+    let page = page.clamp(1, pages);
+    let content = items
+        .iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator);
+
+    if pages == 1 {
+        content
+    } else {
+        format!("{content} (Page {page}/{pages})")
+    }
+}
+
 #[derive(Default, Resource)]
 pub struct CmdCooldown(HashMap<String, Instant>);
 
@@ -106,3 +364,50 @@ impl CmdCooldown {
         false
     }
 }
+
+const MOJANG_URL: &str = "https://api.mojang.com/users/profiles/minecraft";
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Mojang200 {
+    #[serde(rename = "id")]
+    pub uuid: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Mojang404 {
+    #[serde(rename = "errorMessage")]
+    pub error: String,
+    pub path:  String,
+}
+
+/// Fetch a Minecraft UUID from a Username
+///
+/// # Errors
+/// Will return `Err` if `ureq::get`, `Body::read_json`, `Uuid::parse_str`, or Mojang fails.
+pub fn fetch_uuid(username: &str) -> Result<Uuid, (u16, String)> {
+    let url = format!("{MOJANG_URL}/{username}");
+    let response = ureq::get(&url)
+        .call()
+        .map_err(|_| (500, "Mojang request failed.".to_string()))?;
+
+    let status = response.status();
+    let mut body = response.into_body();
+
+    match status.as_u16() {
+        200 => {
+            let resp = body
+                .read_json::<Mojang200>()
+                .map_err(|_| (500, "Mojang parsing json failed.".to_string()))?;
+            Uuid::parse_str(&resp.uuid)
+                .map_err(|_| (500, format!("Mojang parsing uuid failed: {}", resp.uuid)))
+        }
+        404 => {
+            let resp = body
+                .read_json::<Mojang404>()
+                .map_err(|_| (500, "Mojang parsing json failed.".to_string()))?;
+            Err((404, resp.error))
+        }
+        code => Err((code, format!("Mojang Error: {code}"))),
+    }
+}
@@ -1,7 +1,23 @@
 #[cfg(feature = "api")]
 pub use super::parsers::http_api::*;
 pub use super::{
-    commands::{interact::*, join::*, leave::*, pearl::*, playtime::*, seen::*, whitelist::*, *},
+    commands::{
+        account::*,
+        config::*,
+        interact::*,
+        join::*,
+        leave::*,
+        module::*,
+        notify::*,
+        pearl::*,
+        playtime::*,
+        queue::*,
+        seen::*,
+        status::*,
+        verify::*,
+        whitelist::*,
+        *,
+    },
     modules::{
         anti_afk::*,
         auto_eat::*,
@@ -12,12 +28,24 @@ pub use super::{
         auto_totem::*,
         auto_whitelist::*,
         logger::*,
+        zenith_proxy::*,
+        *,
+    },
+    parsers::{console::*, minecraft::*, *},
+    settings::{auth::*, global::*, local::*, stasis::*, watcher::*, *},
+    trackers::{
+        block_state::*,
+        bot_event::*,
+        ender_pearl::*,
+        game_tick::*,
+        metrics::*,
+        player_profile::*,
+        queue::*,
         *,
     },
-    parsers::{minecraft::*, *},
-    settings::{global::*, local::*, stasis::*, *},
-    trackers::{block_state::*, ender_pearl::*, game_tick::*, player_profile::*, *},
     *,
 };
+#[cfg(unix)]
+pub use super::parsers::control::*;
 #[cfg(feature = "bot")]
-pub use super::parsers::discord::*;
+pub use super::{modules::discord_bridge::*, parsers::discord::*};
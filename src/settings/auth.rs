@@ -0,0 +1,253 @@
+#[cfg(unix)]
+use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use azalea::{
+    app::{App, Plugin, Update},
+    auth::{
+        AccessTokenResponse,
+        cache::ExpiringValue,
+        get_ms_auth_token,
+        get_ms_link_code,
+        refresh_ms_auth_token,
+    },
+    ecs::prelude::*,
+    prelude::*,
+    swarm::Swarm,
+};
+#[cfg(feature = "bot")]
+use bevy_discord::res::DiscordHttpResource;
+use serde::Serialize;
+#[cfg(feature = "bot")]
+use serenity::{all::UserId, json::json};
+use tokio::io::AsyncWriteExt;
+
+use crate::prelude::*;
+
+/// Microsoft device-code login prompts over Discord and the API
+pub struct AuthPromptPlugin;
+
+impl Plugin for AuthPromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AuthPrompts::default())
+            .add_message::<AuthEvent>()
+            .add_systems(Update, Self::handle_auth_events);
+    }
+}
+
+/// Pending device-code prompts by account username.
+#[derive(Clone, Debug, Default, Deref, DerefMut, Resource, Serialize)]
+pub struct AuthPrompts(HashMap<String, AuthPrompt>);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthPrompt {
+    pub user_code:        String,
+    pub verification_uri: String,
+    /// Unix timestamp in seconds.
+    pub expires_at:       u64,
+}
+
+#[derive(Clone, Debug, Message)]
+pub struct AuthEvent {
+    pub username: String,
+    pub state:    AuthState,
+}
+
+#[derive(Clone, Debug)]
+pub enum AuthState {
+    Prompt(AuthPrompt),
+    Success,
+    Failed(String),
+}
+
+impl AuthEvent {
+    #[must_use]
+    pub fn content(&self) -> String {
+        let username = &self.username;
+        match &self.state {
+            AuthState::Prompt(prompt) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                let minutes = prompt.expires_at.saturating_sub(now.as_secs()) / 60;
+                format!(
+                    "[{username}] Microsoft login required, go to {} and enter the code {} (expires in {minutes} minutes)",
+                    prompt.verification_uri, prompt.user_code
+                )
+            }
+            AuthState::Success => format!("[{username}] Microsoft login succeeded"),
+            AuthState::Failed(error) => format!("[{username}] Microsoft login failed: {error}"),
+        }
+    }
+}
+
+impl AuthPromptPlugin {
+    pub fn handle_auth_events(
+        mut auth_events: MessageReader<AuthEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        mut auth_prompts: ResMut<AuthPrompts>,
+        #[cfg(feature = "bot")] discord: Option<Res<DiscordHttpResource>>,
+        #[cfg(feature = "bot")] global_settings: Res<GlobalSettings>,
+    ) {
+        for event in auth_events.read() {
+            let content = event.content();
+            let status = match &event.state {
+                AuthState::Prompt(prompt) => {
+                    warn!("{content}");
+                    auth_prompts.insert(event.username.clone(), prompt.clone());
+                    "prompt"
+                }
+                AuthState::Success => {
+                    info!("{content}");
+                    auth_prompts.remove(&event.username);
+                    "success"
+                }
+                AuthState::Failed(_) => {
+                    error!("{content}");
+                    auth_prompts.remove(&event.username);
+                    "failed"
+                }
+            };
+
+            bot_events.write(BotEvent {
+                bot:  event.username.clone(),
+                kind: BotEventKind::Auth {
+                    status:  str!(status),
+                    content: content.clone(),
+                },
+            });
+
+            #[cfg(feature = "bot")]
+            if let Some(discord) = &discord {
+                let client = discord.client();
+                let channel_id = global_settings.auth_channel;
+                let user_ids = global_settings
+                    .admins
+                    .iter()
+                    .filter_map(|uuid| global_settings.users.get(uuid))
+                    .filter_map(|user| user.discord_id.parse::<u64>().ok())
+                    .map(UserId::new)
+                    .collect::<Vec<_>>();
+
+                tokio::task::spawn_local(async move {
+                    let map = &json!({ "content": content });
+                    if let Some(channel_id) = channel_id
+                        && let Err(error) = client.send_message(channel_id, Vec::new(), map).await
+                    {
+                        error!("[Auth] Failed to send to channel: {error}");
+                    }
+
+                    for user_id in user_ids {
+                        let recipient = &json!({ "recipient_id": user_id });
+                        let result = match client.create_private_channel(recipient).await {
+                            Ok(channel) => client.send_message(channel.id, Vec::new(), map).await,
+                            Err(error) => Err(error),
+                        };
+
+                        if let Err(error) = result {
+                            error!("[Auth] Failed to DM {user_id}: {error}");
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Token cache file for an account, inside the configured cache directory.
+///
+/// # Errors
+/// Will return `Err` if `GlobalSettings::path` fails.
+pub fn token_cache_path(auth_cache: &str, username: &str) -> Result<PathBuf> {
+    let path = GlobalSettings::path()?;
+    let dir = path.parent().context("None")?.join(auth_cache);
+
+    Ok(dir.join(format!("{}.json", username.to_lowercase())))
+}
+
+/// Login to a Microsoft account, the device-code prompt is sent as an [`AuthEvent`]
+/// instead of printing to the console, and the token is cached for the next login.
+///
+/// # Errors
+/// Will return `Err` if the device-code flow, token refresh, or cache fails.
+pub async fn login_microsoft(swarm: &Swarm, username: &str, cache: PathBuf) -> Result<Account> {
+    let client = reqwest::Client::new();
+    let cached = tokio::fs::read_to_string(&cache)
+        .await
+        .ok()
+        .and_then(|text| serde_json::from_str::<ExpiringValue<AccessTokenResponse>>(&text).ok());
+
+    let msa = match cached {
+        Some(msa) if !msa.is_expired() => msa,
+        Some(msa) => {
+            let refresh_token = &msa.data.refresh_token;
+            match refresh_ms_auth_token(&client, refresh_token, None, None).await {
+                Ok(msa) => msa,
+                Err(error) => {
+                    warn!("[{username}] Microsoft token refresh failed, logging in again: {error}");
+                    login_device_code(swarm, username, &client).await?
+                }
+            }
+        }
+        None => login_device_code(swarm, username, &client).await?,
+    };
+
+    if let Some(parent) = cache.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    /* The cache holds a refresh token, so only the owner may read it */
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(&cache).await?;
+    #[cfg(unix)]
+    file.set_permissions(Permissions::from_mode(0o600)).await?; /* Cached Before */
+    file.write_all(serde_json::to_string(&msa)?.as_bytes()).await?;
+
+    match Account::with_microsoft_access_token(msa).await {
+        Ok(account) => {
+            send_auth_event(swarm, username, AuthState::Success);
+            Ok(account)
+        }
+        Err(error) => {
+            send_auth_event(swarm, username, AuthState::Failed(error.to_string()));
+            Err(error.into())
+        }
+    }
+}
+
+/// Login with the device-code flow, the prompt is sent as an [`AuthEvent`].
+async fn login_device_code(
+    swarm: &Swarm,
+    username: &str,
+    client: &reqwest::Client,
+) -> Result<ExpiringValue<AccessTokenResponse>> {
+    let res = get_ms_link_code(client, None, None).await?;
+    let expires_at = SystemTime::now() + Duration::from_secs(res.expires_in);
+    let prompt = AuthPrompt {
+        user_code:        res.user_code.clone(),
+        verification_uri: res.verification_uri.clone(),
+        expires_at:       expires_at.duration_since(UNIX_EPOCH)?.as_secs(),
+    };
+
+    send_auth_event(swarm, username, AuthState::Prompt(prompt));
+    match get_ms_auth_token(client, res, None).await {
+        Ok(msa) => Ok(msa),
+        Err(error) => {
+            send_auth_event(swarm, username, AuthState::Failed(error.to_string()));
+            Err(error.into())
+        }
+    }
+}
+
+fn send_auth_event(swarm: &Swarm, username: &str, state: AuthState) {
+    swarm.ecs.write().write_message(AuthEvent {
+        username: str!(username),
+        state,
+    });
+}
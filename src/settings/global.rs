@@ -2,19 +2,36 @@ use std::{
     collections::HashMap,
     fs::File,
     io::{ErrorKind, Read, Seek, Write},
+    ops::Deref,
     path::PathBuf,
     time::Duration,
 };
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+#[cfg(feature = "api")]
+use argon2::{
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        PasswordHash,
+        PasswordHasher,
+        PasswordVerifier,
+        SaltString,
+    },
+    Argon2,
+};
 use azalea::{
     app::{App, Plugin},
     prelude::*,
     protocol::address::ServerAddr,
 };
-use serde::{Deserialize, Serialize};
+use lazy_regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_tuple::{Deserialize_tuple as DeserializeTuple, Serialize_tuple as SerializeTuple};
-use serde_with::DurationSeconds;
+use serde_with::{DurationMilliSeconds, DurationSeconds};
+#[cfg(feature = "bot")]
+use serenity::all::ChannelId;
+#[cfg(feature = "api")]
+use sha2::{Digest, Sha256};
 use smart_default::SmartDefault;
 use uuid::Uuid;
 
@@ -31,18 +48,56 @@ impl Plugin for GlobalSettingsPlugin {
 #[derive(Clone, Deserialize, Serialize, SmartDefault, Resource)]
 #[serde(default)]
 pub struct GlobalSettings {
+    /// Microsoft token cache directory, relative to the config directory.
+    #[default("auth-cache")]
+    pub auth_cache: String,
+
+    /// Discord channel for Microsoft login prompts. (Optional)
+    #[cfg(feature = "bot")]
+    pub auth_channel: Option<ChannelId>,
+
     /// Chat command prefix.
     #[default("!")]
     pub command_prefix: String,
 
+    /// Number of entries per page for long lists such as locations.
+    #[default(10)]
+    pub page_size: usize,
+
     /// Command cooldown in seconds.
     #[default(Duration::from_secs(10))]
     #[serde_as(as = "DurationSeconds")]
     pub command_cooldown: Duration,
 
+    /// Unix domain socket for local control with `shaysbot ctl`. (Unix only)
+    #[default(true)]
+    pub control_socket: bool,
+
+    /// Interval in seconds to check for settings changed on disk. (0 to disable)
+    #[default(Duration::from_secs(2))]
+    #[serde_as(as = "DurationSeconds")]
+    pub hot_reload_interval: Duration,
+
     /// Discord client token for commands and responses. (Optional)
     pub discord_token: String,
 
+    /// Seconds before an account link verification code expires.
+    #[default(Duration::from_secs(300))]
+    #[serde_as(as = "DurationSeconds")]
+    pub link_code_expiry: Duration,
+
+    /// Linked Minecraft accounts that receive stasis chamber notifications in Discord DMs.
+    pub dm_notifications: Vec<Uuid>,
+
+    /// Maximum number of accounts joining at the same time, after Microsoft authentication.
+    #[default(1)]
+    pub login_concurrency: usize,
+
+    /// Delay in seconds before each account logs in.
+    #[default(Duration::from_secs(5))]
+    #[serde_as(as = "DurationSeconds")]
+    pub login_stagger: Duration,
+
     /// Logger configuration for sending game events to Discord via webhooks.
     #[serde(default)]
     pub logger: LoggerConfig,
@@ -62,6 +117,10 @@ pub struct GlobalSettings {
     /// `ViaProxy` server version. (Optional)
     pub server_version: String,
 
+    /// Queue positions that post a webhook when a bot passes them.
+    #[default(vec![500, 100, 10])]
+    pub queue_thresholds: Vec<u32>,
+
     /// Automatically whitelist players that enter visual range.
     #[default(false)]
     pub whitelist_in_range: bool,
@@ -79,10 +138,27 @@ pub struct GlobalSettings {
     #[serde(rename = "chat_encryption")]
     pub chat: ChatEncryption,
 
-    /// Minecraft accounts with their linked Discord ID and API Password.
+    /// Reconnect policies matched against disconnect reasons.
+    pub reconnect: ReconnectSettings,
+
+    /// Named chat profiles that server profiles can reference with `chat_profile`.
+    pub chat_profiles: HashMap<String, ChatProfile>,
+
+    /// Named server profiles that accounts can reference with `server`.
+    pub servers: HashMap<String, ServerProfile>,
+
+    /// Minecraft accounts allowed to run admin commands. (`config`, `module`)
+    pub admins: Vec<Uuid>,
+
+    /// Minecraft accounts with their linked Discord ID and API Password hash.
     pub users: HashMap<Uuid, User>,
+
+    /// Named API bearer tokens, secrets are stored as SHA-256 hashes.
+    #[cfg(feature = "api")]
+    pub api_tokens: Vec<ApiToken>,
 }
 
+#[serde_as]
 #[derive(Clone, Eq, PartialEq, Deserialize, Serialize, SmartDefault)]
 #[serde(default)]
 pub struct ApiServer {
@@ -92,6 +168,29 @@ pub struct ApiServer {
     /// API Server bind address. (default local only & random port)
     #[default("127.0.0.1:0")]
     pub bind_addr: String,
+
+    /// Failed logins from one IP address or username before it's locked out.
+    #[default(5)]
+    pub max_failures: u32,
+
+    /// First lockout in seconds, doubled for every further failure.
+    #[default(Duration::from_secs(30))]
+    #[serde_as(as = "DurationSeconds")]
+    pub lockout: Duration,
+
+    /// Longest lockout in seconds.
+    #[default(Duration::from_secs(3600))]
+    #[serde_as(as = "DurationSeconds")]
+    pub max_lockout: Duration,
+
+    /// Seconds to wait for a command response before answering with 504.
+    #[default(Duration::from_secs(30))]
+    #[serde_as(as = "DurationSeconds")]
+    pub response_timeout: Duration,
+
+    /// Audit log of logins and privileged actions, relative to the config directory. (Optional)
+    #[default("api-audit.jsonl")]
+    pub audit_log: String,
 }
 
 #[derive(Clone, Eq, PartialEq, Deserialize, Serialize, SmartDefault)]
@@ -106,6 +205,200 @@ pub struct ChatEncryption {
     pub mode: EncryptionMode,
 }
 
+#[derive(Clone, Deserialize, Serialize, SmartDefault)]
+#[serde(default)]
+pub struct ServerProfile {
+    /// Minecraft server address.
+    #[default(ServerAddr{
+        host: str!("localhost"),
+        port: 25565
+    })]
+    pub address: ServerAddr,
+
+    /// Chat profile name from `chat_profiles`. (Optional)
+    pub chat_profile: String,
+
+    /// Track the 2B2T queue position of accounts on this server.
+    #[default(true)]
+    pub queue: bool,
+
+    /// Players allowed to use commands on this server, empty allows every whitelisted player.
+    pub whitelist: Vec<Uuid>,
+}
+
+#[serde_as]
+#[derive(Clone, Deserialize, Serialize, SmartDefault)]
+#[serde(default)]
+pub struct ChatProfile {
+    /// Public chat regex with `username` and `content` groups.
+    #[default(SettingsRegex::new(
+        r"^(?:\[.+\] )?(?P<username>[a-zA-Z_0-9]{1,16}) (?:> )?(?P<content>.+)$"
+    ))]
+    pub public: SettingsRegex,
+
+    /// Whisper regex with `username` and `content` groups, checked before `public`.
+    #[default(SettingsRegex::new(
+        r"^(?:\[.+\] )?(?P<username>[a-zA-Z_0-9]{1,16}) (?:whispers: |-> me\] )(?P<content>.+)$"
+    ))]
+    pub whisper: SettingsRegex,
+
+    /// Whisper command template with `{username}` and `{message}`, without the slash.
+    #[default("w {username} {message}")]
+    pub whisper_template: String,
+
+    /// Maximum length of a chat message or command.
+    #[default(256)]
+    pub max_length: usize,
+
+    /// Minimum delay in milliseconds between sent messages.
+    #[default(Duration::from_millis(500))]
+    #[serde_as(as = "DurationMilliSeconds")]
+    pub send_delay: Duration,
+}
+
+impl ChatProfile {
+    /// Check that every regex has the `username` and `content` groups, empty regexes are disabled.
+    ///
+    /// # Errors
+    /// Will return `Err` if a regex is missing a group.
+    pub fn validate(&self) -> Result<()> {
+        for regex in [&self.public, &self.whisper] {
+            if regex.as_str().is_empty() {
+                continue; /* Disabled */
+            }
+
+            for group in ["username", "content"] {
+                if !regex.capture_names().flatten().any(|name| name == group) {
+                    let pattern = regex.as_str();
+                    bail!("Chat profile regex {pattern} is missing the {group} group");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A regex compiled when settings are loaded, so invalid patterns are rejected with the file.
+#[derive(Clone, Debug)]
+pub struct SettingsRegex(Regex);
+
+impl SettingsRegex {
+    /// # Panics
+    /// Will panic if the pattern is invalid, only use this for defaults.
+    #[must_use]
+    pub fn new(pattern: &str) -> Self {
+        Self(Regex::new(pattern).expect("Invalid default regex"))
+    }
+}
+
+impl Deref for SettingsRegex {
+    type Target = Regex;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Serialize for SettingsRegex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SettingsRegex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map(Self).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, SmartDefault)]
+#[serde(default)]
+pub struct ReconnectSettings {
+    /// Maximum reconnect attempts without a successful login. (0 for unlimited)
+    #[default(0)]
+    pub max_attempts: u32,
+
+    /// Rules are checked in order, the first pattern that matches the reason is used.
+    #[default(vec![
+        ReconnectRule::new("zenith", r"^\[AutoDisconnect\] ", ReconnectAction::Reconnect {
+            delay: Duration::from_secs(10),
+        }),
+        ReconnectRule::new("leave", "^Leave Command: ", ReconnectAction::Stop),
+        ReconnectRule::new("account", "^Account Command: ", ReconnectAction::Stop),
+        ReconnectRule::new("throttled", "^Connection throttled", ReconnectAction::Reconnect {
+            delay: Duration::from_secs(30),
+        }),
+        ReconnectRule::new("default", ".*", ReconnectAction::Backoff {
+            base:   Duration::from_secs(10),
+            max:    Duration::from_secs(300),
+            jitter: 0.25,
+        }),
+    ])]
+    pub rules: Vec<ReconnectRule>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ReconnectRule {
+    /// Short name used as the reason label of the reconnect metrics.
+    #[serde(default)]
+    pub name: String,
+
+    /// Regex matched against the disconnect reason.
+    pub pattern: SettingsRegex,
+
+    #[serde(flatten)]
+    pub action: ReconnectAction,
+}
+
+impl ReconnectRule {
+    #[must_use]
+    pub fn new(name: &str, pattern: &str, action: ReconnectAction) -> Self {
+        Self {
+            name: str!(name),
+            pattern: SettingsRegex::new(pattern),
+            action,
+        }
+    }
+
+    /// # Errors
+    /// Will return `Err` if the backoff jitter isn't a finite number.
+    pub fn validate(&self) -> Result<()> {
+        if let ReconnectAction::Backoff { jitter, .. } = self.action
+            && !jitter.is_finite()
+        {
+            let pattern = self.pattern.as_str();
+            bail!("Reconnect rule {pattern} has an invalid jitter: {jitter}");
+        }
+
+        Ok(())
+    }
+}
+
+#[serde_as]
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ReconnectAction {
+    /// Reconnect after a fixed delay in seconds.
+    Reconnect {
+        #[serde_as(as = "DurationSeconds")]
+        delay: Duration,
+    },
+    /// Reconnect after `base * 2^attempts` seconds, capped at `max`, randomized by `jitter`.
+    Backoff {
+        #[serde_as(as = "DurationSeconds")]
+        base:   Duration,
+        #[serde_as(as = "DurationSeconds")]
+        max:    Duration,
+        jitter: f64,
+    },
+    /// Disable `AutoReconnect` until the join command is used.
+    Stop,
+    /// Disable `AutoReconnect` until the next local time of day. (`HH:MM`)
+    StopUntil { until: String },
+}
+
 #[serde_as]
 #[derive(Clone, Default, Eq, PartialEq, DeserializeTuple, SerializeTuple)]
 pub struct User {
@@ -113,6 +406,63 @@ pub struct User {
     pub api_password: String,
 }
 
+/// A named, revocable API bearer token. (`sbt_<id>_<secret>`)
+#[cfg(feature = "api")]
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ApiToken {
+    pub id:      String,
+    pub name:    String,
+    pub owner:   Uuid,
+    pub scope:   ApiScope,
+    /// Lowercase hex SHA-256 digest of the secret.
+    pub hash:    String,
+    /// Unix timestamp in seconds.
+    pub created: u64,
+}
+
+/// What an API token is allowed to do.
+#[cfg(feature = "api")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    /// Everything the owner can do with their password.
+    #[default]
+    Full,
+    /// Only the `pearl` command.
+    Pearl,
+    /// Only status routes and commands that don't change anything.
+    #[serde(alias = "read")]
+    ReadOnly,
+}
+
+#[cfg(feature = "api")]
+impl ApiScope {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(Self::Full),
+            "pearl" => Some(Self::Pearl),
+            "read" | "read_only" => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Pearl => "pearl",
+            Self::ReadOnly => "read_only",
+        }
+    }
+
+    /// Whether this scope can use routes that only read state, such as `/queue`.
+    #[must_use]
+    pub const fn can_read(self) -> bool {
+        !matches!(self, Self::Pearl)
+    }
+}
+
 /// Logger configuration for sending game events to Discord via webhooks.
 #[derive(Clone, Deserialize, Serialize, SmartDefault)]
 #[serde(default)]
@@ -168,6 +518,12 @@ pub struct EventTypes {
     /// Chat messages received from other players.
     pub player_chat: WebhookEventConfig,
 
+    /// Queue position passed one of the `queue_thresholds`.
+    pub queue_threshold: WebhookEventConfig,
+
+    /// Left the queue and reached the server.
+    pub queue_connected: WebhookEventConfig,
+
     /// Server-side disconnect (reason from server).
     pub server_disconnect: WebhookEventConfig,
 
@@ -176,6 +532,9 @@ pub struct EventTypes {
 
     /// Connection errors (timeout, auth failure, etc.).
     pub server_error: WebhookEventConfig,
+
+    /// Repeated failed API logins that caused a lockout.
+    pub api_alert: WebhookEventConfig,
 }
 
 /// Configuration for a single event type's webhook logging.
@@ -269,6 +628,21 @@ impl GlobalSettings {
         Ok(path.join("global-settings.toml"))
     }
 
+    /// Get a server profile by name, unknown or empty names use the global server.
+    #[must_use]
+    pub fn server_profile(&self, name: &str) -> ServerProfile {
+        self.servers.get(name).cloned().unwrap_or_else(|| ServerProfile {
+            address: self.server_address.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Get a chat profile by name, unknown or empty names use the default profile.
+    #[must_use]
+    pub fn chat_profile(&self, name: &str) -> ChatProfile {
+        self.chat_profiles.get(name).cloned().unwrap_or_default()
+    }
+
     /// # Errors
     /// Will return `Err` if `File::open`, `toml::to_string_pretty`, or `File::write_all` fails.
     pub fn load() -> Result<Self> {
@@ -281,9 +655,55 @@ impl GlobalSettings {
                 file.read_to_string(&mut text)?;
                 file.rewind()?;
 
-                Ok(toml::from_str(&text)?)
+                #[allow(unused_mut)]
+                let mut settings: Self = toml::from_str(&text)?;
+                for chat_profile in settings.chat_profiles.values() {
+                    chat_profile.validate()?;
+                }
+
+                for rule in &settings.reconnect.rules {
+                    rule.validate()?;
+                }
+
+                #[cfg(feature = "api")]
+                if settings.hash_api_passwords()? {
+                    info!("Migrated plaintext API passwords to argon2 hashes");
+                    settings.save()?;
+                }
+
+                Ok(settings)
+            }
+        }
+    }
+
+    /// Hash any API passwords still stored in plaintext, returns whether any changed.
+    ///
+    /// # Errors
+    /// Will return `Err` if `hash_secret` fails.
+    #[cfg(feature = "api")]
+    pub fn hash_api_passwords(&mut self) -> Result<bool> {
+        let mut changed = false;
+        for user in self.users.values_mut() {
+            if user.api_password.is_empty() || PasswordHash::new(&user.api_password).is_ok() {
+                continue; /* Unset or Hashed */
             }
+
+            user.api_password = hash_secret(&user.api_password)?;
+            changed = true;
         }
+
+        Ok(changed)
+    }
+
+    /// Find the API token matching a bearer token. (`sbt_<id>_<secret>`)
+    #[cfg(feature = "api")]
+    #[must_use]
+    pub fn find_api_token(&self, bearer: &str) -> Option<&ApiToken> {
+        let (id, secret) = bearer.strip_prefix("sbt_")?.split_once('_')?;
+        self.api_tokens
+            .iter()
+            .find(|token| token.id == id)
+            .filter(|token| verify_token_secret(secret, &token.hash))
     }
 
     /// # Errors
@@ -303,3 +723,60 @@ impl GlobalSettings {
         Ok(())
     }
 }
+
+/// Hash a password with argon2 and a random salt.
+///
+/// # Errors
+/// Will return `Err` if `Argon2::hash_password` fails.
+#[cfg(feature = "api")]
+pub fn hash_secret(secret: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map_err(|error| anyhow!("{error}"))?;
+
+    Ok(hash.to_string())
+}
+
+/// Verify a password against an argon2 hash in constant time.
+#[cfg(feature = "api")]
+#[must_use]
+pub fn verify_secret(secret: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(secret.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// Hash a bearer token secret with SHA-256, secrets are random so they don't need a slow KDF.
+#[cfg(feature = "api")]
+#[must_use]
+pub fn hash_token_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Verify a bearer token secret against a SHA-256 hash in constant time.
+#[cfg(feature = "api")]
+#[must_use]
+pub fn verify_token_secret(secret: &str, hash: &str) -> bool {
+    let digest = hash_token_secret(secret);
+    digest.len() == hash.len()
+        && digest
+            .bytes()
+            .zip(hash.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Generate a random lowercase hex string from `bytes` random bytes.
+#[cfg(feature = "api")]
+#[must_use]
+pub fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0; bytes];
+    OsRng.fill_bytes(&mut buf);
+    buf.iter().map(|byte| format!("{byte:02x}")).collect()
+}
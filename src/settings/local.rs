@@ -1,7 +1,9 @@
 use std::{
+    collections::HashMap,
     fs::File,
     io::{ErrorKind, Read, Seek, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 
@@ -17,10 +19,13 @@ use azalea::{
     Vec3,
 };
 use serde::{Deserialize, Serialize};
-use serde_with::DisplayFromStr;
+use serde_with::{DisplayFromStr, DurationSeconds};
 #[cfg(feature = "bot")]
 use serenity::all::ChannelId;
 use smart_default::SmartDefault;
+use tokio::sync::Semaphore;
+
+use crate::prelude::{login_microsoft, token_cache_path, ChatOutbox, GlobalSettings};
 
 /// Local Account Settings that apply per-account
 pub struct LocalSettingsPlugin;
@@ -32,11 +37,17 @@ impl Plugin for LocalSettingsPlugin {
 }
 
 impl LocalSettingsPlugin {
-    pub fn handle_startup(swarm: Res<Swarm>) {
+    pub fn handle_startup(
+        mut commands: Commands,
+        global_settings: Res<GlobalSettings>,
+        swarm: Res<Swarm>,
+    ) {
+        let login_queue = LoginQueue::new(&global_settings);
         let swarm = swarm.clone();
 
+        commands.insert_resource(login_queue.clone());
         tokio::task::spawn_local(async move {
-            if let Err(error) = load_settings(swarm).await {
+            if let Err(error) = load_settings(swarm, login_queue).await {
                 error!("There was an error loading local settings: {error}");
                 std::process::exit(1);
             }
@@ -44,6 +55,84 @@ impl LocalSettingsPlugin {
     }
 }
 
+/// Limits how many accounts log in at once and how long to wait between them.
+#[derive(Clone, Resource)]
+pub struct LoginQueue {
+    auth_cache: String,
+    semaphore:  Arc<Semaphore>,
+    servers:    HashMap<String, ServerAddr>,
+    stagger:    Duration,
+}
+
+impl LoginQueue {
+    #[must_use]
+    pub fn new(global_settings: &GlobalSettings) -> Self {
+        Self {
+            auth_cache: global_settings.auth_cache.clone(),
+            semaphore:  Arc::new(Semaphore::new(global_settings.login_concurrency.max(1))),
+            servers:    global_settings
+                .servers
+                .iter()
+                .map(|(name, profile)| (name.clone(), profile.address.clone()))
+                .collect(),
+            stagger:    global_settings.login_stagger,
+        }
+    }
+
+    /// Add an account to the swarm once a login slot is available.
+    /// Microsoft authentication happens before taking a slot, so a pending device code
+    /// doesn't hold up the other accounts.
+    ///
+    /// # Errors
+    /// Will return `Err` if `login_microsoft` or `resolve_address` fails.
+    pub async fn add_account(&self, swarm: Swarm, settings: LocalSettings) -> Result<()> {
+        let username = settings.username();
+        let account = match settings.auth_mode {
+            AuthMode::Offline => Account::offline(&username),
+            AuthMode::Online => {
+                let cache = token_cache_path(&self.auth_cache, &username)?;
+                login_microsoft(&swarm, &username, cache).await?
+            }
+        };
+
+        let _permit = self.semaphore.acquire().await?;
+
+        if !settings.server.is_empty() && !self.servers.contains_key(&settings.server) {
+            warn!("[{username}] Unknown server profile: {}", settings.server);
+        }
+
+        tokio::time::sleep(self.stagger).await;
+        if !settings.file_path().exists() {
+            info!("[{username}] Account was removed while queued, skipping login");
+            return Ok(());
+        }
+
+        let server_address = settings
+            .server_address
+            .clone()
+            .or_else(|| self.servers.get(&settings.server).cloned());
+
+        let client = if let Some(server_address) = server_address {
+            let Ok(resolved_address) = resolve::resolve_address(&server_address).await else {
+                bail!("Failed to resolve server address")
+            };
+
+            let opts = JoinOpts::new()
+                .custom_server_addr(server_address)
+                .custom_socket_addr(resolved_address);
+
+            swarm.add_with_opts(&account, NoState, &opts).await
+        } else {
+            swarm.add(&account, NoState).await /* Use the default server address */
+        };
+
+        let mut world = client.ecs.write();
+        world.commands().entity(client.entity).insert((settings, ChatOutbox::default()));
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Component, Deserialize, Serialize, SmartDefault)]
 #[serde(default)]
 pub struct LocalSettings {
@@ -77,12 +166,22 @@ pub struct LocalSettings {
     /// Disable in-game command responses.
     pub disable_responses: bool,
 
+    /// Discord chat bridge module settings.
+    #[cfg(feature = "bot")]
+    pub discord_bridge: DiscordBridge,
+
     /// Discord Channel ID. (Optional)
     #[cfg(feature = "bot")]
     pub discord_channel: ChannelId,
 
-    /// Minecraft account server address. (Optional)
+    /// Server profile name from `servers` in the global settings. (Optional)
+    pub server: String,
+
+    /// Minecraft account server address, intended for proxies. (Optional)
     pub server_address: Option<ServerAddr>,
+
+    /// ZenithProxy module settings.
+    pub zenith_proxy: ZenithProxy,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
@@ -115,8 +214,22 @@ pub struct AntiSpam {
     #[default(true)]
     pub enabled: bool,
 
+    /// Append the unix epoch to responses so they're never identical.
     #[default(true)]
     pub unix_epoch: bool,
+
+    /// Append this many random characters to responses. (0 to disable)
+    #[default(0)]
+    pub random_suffix: usize,
+
+    /// Drop responses identical to one sent within this many seconds. (Ignored with a suffix)
+    #[default(Duration::from_secs(30))]
+    #[serde_as(as = "DurationSeconds")]
+    pub duplicate_window: Duration,
+
+    /// Maximum number of queued outbound messages. (0 for unlimited)
+    #[default(20)]
+    pub max_queue: usize,
 }
 
 #[serde_as]
@@ -201,6 +314,39 @@ pub struct IdleGoal {
     pub radius: f32,
 }
 
+#[cfg(feature = "bot")]
+#[derive(Clone, Deserialize, Serialize, SmartDefault)]
+#[serde(default)]
+pub struct DiscordBridge {
+    /// Relay chat between the game and `discord_channel`.
+    #[default(false)]
+    pub enabled: bool,
+
+    /// Relay player join and leave messages.
+    #[default(true)]
+    pub joins: bool,
+
+    /// Relay player death messages.
+    #[default(true)]
+    pub deaths: bool,
+
+    /// Relay whispers sent to the bot, commands are never relayed.
+    #[default(false)]
+    pub whispers: bool,
+}
+
+#[derive(Clone, Deserialize, Serialize, SmartDefault)]
+#[serde(default)]
+pub struct ZenithProxy {
+    /// Control `ZenithProxy` with its in-game commands, requires `server_address`.
+    #[default(false)]
+    pub enabled: bool,
+
+    /// `ZenithProxy` in-game command prefix.
+    #[default("$")]
+    pub command_prefix: String,
+}
+
 impl From<PathBuf> for LocalSettings {
     fn from(path: PathBuf) -> Self {
         Self {
@@ -230,6 +376,91 @@ impl LocalSettings {
         Ok(path.join("local-settings"))
     }
 
+    /// The settings file this account was loaded from.
+    #[must_use]
+    pub fn file_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the settings file again without creating it.
+    ///
+    /// # Errors
+    /// Will return `Err` if `std::fs::read_to_string` or `toml::from_str` fails.
+    pub fn reload(&self) -> Result<Self> {
+        let text = std::fs::read_to_string(&self.path)?;
+        let mut local_settings = toml::from_str::<Self>(&text)?;
+        local_settings.path = self.path.clone(); /* Fix serde replacing path */
+
+        Ok(local_settings)
+    }
+
+    /// The account username, taken from the settings file name.
+    #[must_use]
+    pub fn username(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Check a location name, optionally qualified by the server profile. (`server:location`)
+    #[must_use]
+    pub fn is_location(&self, location: &str) -> bool {
+        let own_location = &self.auto_pearl.location;
+        match location.split_once(':') {
+            Some((server, location)) => server == self.server && location == own_location,
+            None => location == own_location,
+        }
+    }
+
+    /// Get a setting by its dotted path, e.g. `auto_look.delay_ticks`.
+    ///
+    /// # Errors
+    /// Will return `Err` if the settings fail to serialize or the path doesn't exist.
+    pub fn get_value(&self, path: &str) -> Result<toml::Value> {
+        let root = toml::Value::try_from(self)?;
+        let value = path
+            .split('.')
+            .try_fold(&root, |value, key| value.get(key))
+            .with_context(|| format!("Unknown setting: {path}"))?;
+
+        Ok(value.clone())
+    }
+
+    /// Set a setting by its dotted path, the new value must match the existing type.
+    ///
+    /// # Errors
+    /// Will return `Err` if the path doesn't exist or the value fails validation.
+    pub fn set_value(&mut self, path: &str, raw: &str) -> Result<toml::Value> {
+        let mut root = toml::Value::try_from(&*self)?;
+        let slot = path
+            .split('.')
+            .try_fold(&mut root, |value, key| value.get_mut(key))
+            .with_context(|| format!("Unknown setting: {path}"))?;
+
+        let value = if slot.is_str() {
+            toml::Value::String(str!(raw.trim_matches('"')))
+        } else {
+            toml::from_str::<toml::Table>(&format!("value = {raw}"))
+                .ok()
+                .and_then(|mut table| table.remove("value"))
+                .with_context(|| format!("Invalid value: {raw}"))?
+        };
+
+        if slot.type_str() != value.type_str() {
+            bail!("Expected {} for {path}, got {}", slot.type_str(), value.type_str());
+        }
+
+        *slot = value.clone();
+
+        /* Round-trip through serde to validate against the schema */
+        let mut local_settings = root.try_into::<Self>()?;
+        local_settings.path = self.path.clone();
+        *self = local_settings;
+
+        Ok(value)
+    }
+
     /// # Errors
     /// Will return `Err` if `File::open`, `toml::to_string_pretty`, or `File::write_all` fails.
     pub fn load(self) -> Result<Self> {
@@ -268,7 +499,7 @@ impl LocalSettings {
 
 /// # Errors
 /// Will return `Err` if settings fails to load.
-pub async fn load_settings(swarm: Swarm) -> Result<()> {
+pub async fn load_settings(swarm: Swarm, login_queue: LoginQueue) -> Result<()> {
     let path = LocalSettings::path()?;
     if !path.exists() {
         tokio::fs::create_dir(&path).await?;
@@ -293,28 +524,13 @@ pub async fn load_settings(swarm: Swarm) -> Result<()> {
 
     for username in usernames {
         let settings = LocalSettings::new(&username)?.load()?.save()?;
-        let account = match settings.auth_mode {
-            AuthMode::Offline => Account::offline(&username),
-            AuthMode::Online => Account::microsoft(&username).await?,
-        };
-
-        tokio::time::sleep(Duration::from_secs(5)).await;
-        let client = if let Some(server_address) = settings.server_address.clone() {
-            let Ok(resolved_address) = resolve::resolve_address(&server_address).await else {
-                bail!("Failed to resolve server address")
-            };
+        let (swarm, login_queue) = (swarm.clone(), login_queue.clone());
 
-            let opts = JoinOpts::new()
-                .custom_server_addr(server_address)
-                .custom_socket_addr(resolved_address);
-
-            swarm.add_with_opts(&account, NoState, &opts).await
-        } else {
-            swarm.add(&account, NoState).await /* Use the default server address */
-        };
-
-        let mut world = client.ecs.write();
-        world.commands().entity(client.entity).insert(settings);
+        tokio::task::spawn_local(async move {
+            if let Err(error) = login_queue.add_account(swarm, settings).await {
+                error!("[{username}] There was an error adding the account: {error}");
+            }
+        });
     }
 
     Ok(())
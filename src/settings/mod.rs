@@ -2,17 +2,21 @@ use azalea::app::{PluginGroup, PluginGroupBuilder};
 
 use crate::prelude::*;
 
+pub mod auth;
 pub mod global;
 pub mod local;
 pub mod stasis;
+pub mod watcher;
 
 pub struct SettingsPluginGroup;
 
 impl PluginGroup for SettingsPluginGroup {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
+            .add(AuthPromptPlugin)
             .add(GlobalSettingsPlugin)
             .add(LocalSettingsPlugin)
             .add(StasisChambersPlugin)
+            .add(SettingsWatcherPlugin)
     }
 }
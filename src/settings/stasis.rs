@@ -34,6 +34,15 @@ pub struct StasisChamber {
     pub entity_id:  MinecraftEntityId,
     pub owner_uuid: Uuid,
     pub location:   String,
+    pub server:     String,
+}
+
+impl StasisChamber {
+    /// Check if the chamber belongs to an account's server profile and location.
+    #[must_use]
+    pub fn is_at(&self, server: &str, location: &str) -> bool {
+        self.server == server && self.location == location
+    }
 }
 
 #[serde_as]
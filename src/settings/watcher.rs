@@ -0,0 +1,210 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::Result;
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+    player::GameProfileComponent,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::prelude::*;
+
+/// Hot-reload settings and stasis chambers from disk
+pub struct SettingsWatcherPlugin;
+
+impl Plugin for SettingsWatcherPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SettingsWatcher::default())
+            .add_systems(Update, Self::handle_settings_watcher);
+    }
+}
+
+/// Settings that are only read on startup and need a restart to apply.
+const RESTART_REQUIRED: &[&str] = &[
+    "api_server",
+    "control_socket",
+    "discord_token",
+    "logger",
+    "server_address",
+    "server_version",
+];
+
+#[derive(Default, Resource)]
+pub struct SettingsWatcher {
+    last_check: Option<Instant>,
+    modified:   HashMap<PathBuf, SystemTime>,
+}
+
+impl SettingsWatcher {
+    /// Returns true if the file was modified since it was last seen.
+    /// The first time a file is seen it's assumed to be already loaded.
+    fn is_modified(&mut self, path: &Path) -> bool {
+        let Ok(modified) = std::fs::metadata(path).and_then(|metadata| metadata.modified()) else {
+            return false; /* Missing */
+        };
+
+        match self.modified.insert(path.to_path_buf(), modified) {
+            Some(previous) => previous != modified,
+            None => false,
+        }
+    }
+}
+
+impl SettingsWatcherPlugin {
+    pub fn handle_settings_watcher(
+        mut query: Query<(&GameProfileComponent, &mut LocalSettings)>,
+        mut global_settings: ResMut<GlobalSettings>,
+        mut stasis_chambers: ResMut<StasisChambers>,
+        mut watcher: ResMut<SettingsWatcher>,
+    ) {
+        let interval = global_settings.hot_reload_interval;
+        if interval.is_zero() {
+            return; /* Hot-reload: Disabled */
+        }
+
+        if watcher
+            .last_check
+            .is_some_and(|instant| instant.elapsed() < interval)
+        {
+            return;
+        }
+
+        watcher.last_check = Some(Instant::now());
+
+        if let Ok(path) = GlobalSettings::path()
+            && watcher.is_modified(&path)
+        {
+            match GlobalSettings::load() {
+                Ok(new_settings) => {
+                    let changes = diff(&*global_settings, &new_settings);
+                    if !changes.is_empty() {
+                        info!(
+                            "[Settings] Reloaded global settings: {}",
+                            changes.join(", ")
+                        );
+                        let restart = changes
+                            .iter()
+                            .filter(|key| RESTART_REQUIRED.iter().any(|name| key.starts_with(name)))
+                            .collect::<Vec<_>>();
+
+                        if !restart.is_empty() {
+                            warn!("[Settings] Restart required to apply: {restart:?}");
+                        }
+
+                        *global_settings = new_settings;
+                    }
+                }
+                Err(error) => {
+                    error!("[Settings] Rejected {}: {error:#}", path.display());
+                }
+            }
+        }
+
+        if let Ok(path) = StasisChambers::path()
+            && watcher.is_modified(&path)
+        {
+            match StasisChambers::load() {
+                Ok(new_chambers) => {
+                    let (added, removed, changed) = diff_chambers(&stasis_chambers, &new_chambers);
+                    if added + removed + changed > 0 {
+                        info!(
+                            "[Settings] Reloaded stasis chambers: {added} added, {removed} removed, {changed} changed"
+                        );
+
+                        *stasis_chambers = new_chambers;
+                    }
+                }
+                Err(error) => {
+                    error!("[Settings] Rejected {}: {error:#}", path.display());
+                }
+            }
+        }
+
+        for (profile, mut local_settings) in &mut query {
+            let path = local_settings.file_path().to_path_buf();
+            if !watcher.is_modified(&path) {
+                continue;
+            }
+
+            match local_settings.reload() {
+                Ok(new_settings) => {
+                    let changes = diff(&*local_settings, &new_settings);
+                    if !changes.is_empty() {
+                        let name = &profile.name;
+                        info!(
+                            "[Settings] Reloaded {name} local settings: {}",
+                            changes.join(", ")
+                        );
+                        if changes.iter().any(|key| key.starts_with("auth_mode")) {
+                            warn!("[Settings] Restart required to apply: [\"auth_mode\"]");
+                        }
+
+                        *local_settings = new_settings;
+                    }
+                }
+                Err(error) => {
+                    error!("[Settings] Rejected {}: {error:#}", path.display());
+                }
+            }
+        }
+    }
+}
+
+/// Dotted paths of every value that differs between the two settings.
+fn diff<T: Serialize>(old: &T, new: &T) -> Vec<String> {
+    let (Ok(old), Ok(new)) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+    diff_values("", &old, &new, &mut changes);
+
+    changes
+}
+
+fn diff_values(prefix: &str, old: &Value, new: &Value, changes: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_values(&path, old, new, changes),
+                    _ => changes.push(path),
+                }
+            }
+        }
+        (old, new) if old != new => changes.push(str!(prefix)),
+        _ => {}
+    }
+}
+
+/// Count the added, removed, and changed stasis chambers.
+fn diff_chambers(old: &StasisChambers, new: &StasisChambers) -> (usize, usize, usize) {
+    let added = new.0.keys().filter(|id| !old.0.contains_key(id)).count();
+    let removed = old.0.keys().filter(|id| !new.0.contains_key(id)).count();
+    let changed = new
+        .0
+        .iter()
+        .filter(|(id, chamber)| {
+            old.0.get(id).is_some_and(|old| {
+                serde_json::to_value(old).ok() != serde_json::to_value(chamber).ok()
+            })
+        })
+        .count();
+
+    (added, removed, changed)
+}
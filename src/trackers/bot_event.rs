@@ -0,0 +1,447 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::Sender,
+    },
+    time::Instant,
+};
+
+use azalea::{
+    app::{App, Plugin, PostUpdate, Update},
+    client_chat::ChatReceivedEvent,
+    disconnect::DisconnectEvent,
+    ecs::prelude::*,
+    local_player::TabList,
+    packet::{game::ReceiveGamePacketEvent, login::ReceiveLoginPacketEvent},
+    player::GameProfileComponent,
+    prelude::*,
+    protocol::packets::{game::ClientboundGamePacket, login::ClientboundLoginPacket},
+    registry::builtin::EntityKind,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+/// Replaces arguments and responses of commands that handle secrets.
+pub const REDACTED: &str = "[redacted]";
+
+/// Tracks swarm activity for event subscribers
+pub struct BotEventPlugin;
+
+impl Plugin for BotEventPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EventSubscribers::default())
+            .add_message::<BotEvent>()
+            .add_systems(
+                Update,
+                (
+                    Self::handle_add_entity_packets,
+                    Self::handle_chamber_events,
+                    Self::handle_chat_received_events,
+                    Self::handle_cmd_events,
+                    Self::handle_disconnect_events,
+                    Self::handle_login_packets,
+                    Self::handle_msg_events,
+                    Self::handle_pearl_goto_events,
+                    Self::handle_queue_events,
+                ),
+            )
+            .add_systems(PostUpdate, Self::handle_bot_events);
+    }
+}
+
+/// A typed swarm event, serialized as a single JSON object for subscribers.
+#[derive(Clone, Debug, Message, Serialize)]
+pub struct BotEvent {
+    /// Bot account username, empty for swarm-wide events.
+    pub bot:  String,
+    #[serde(flatten)]
+    pub kind: BotEventKind,
+}
+
+#[derive(Clone, Debug, IntoStaticStr, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BotEventKind {
+    /// Microsoft login prompt, success, or failure.
+    Auth { status: String, content: String },
+    /// Stasis chamber registered, broken, or pulled.
+    Chamber {
+        action:    ChamberEventKind,
+        owner:     Uuid,
+        block_pos: String,
+    },
+    /// Chat message received by the bot.
+    Chat { message: String },
+    /// Command received from any frontend.
+    Command {
+        source:  String,
+        sender:  String,
+        command: String,
+        args:    Vec<String>,
+    },
+    /// Player entered the bot's visual range.
+    Enter { uuid: Uuid, name: String },
+    /// Bot finished logging in.
+    Join,
+    /// Bot disconnected from the server.
+    Leave { reason: String },
+    /// Bot is on its way to pull a pearl.
+    Pearl { owner: Uuid, block_pos: String },
+    /// Queue position changed, `None` once the bot reaches the server.
+    Queue {
+        position: Option<u32>,
+        eta:      Option<String>,
+    },
+    /// Command response sent through any frontend.
+    Response {
+        source:  String,
+        status:  u16,
+        content: String,
+    },
+}
+
+impl BotEvent {
+    #[must_use]
+    pub fn kind_name(&self) -> &'static str {
+        (&self.kind).into()
+    }
+}
+
+/// Server-side filter for event subscriptions, empty lists match everything.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    pub types: Vec<String>,
+    pub bots:  Vec<String>,
+}
+
+impl EventFilter {
+    #[must_use]
+    pub fn matches(&self, event: &BotEvent) -> bool {
+        let kind = event.kind_name();
+        let type_matches = self.types.is_empty() || self.types.iter().any(|t| t == kind);
+        let bot_matches = self.bots.is_empty()
+            || self
+                .bots
+                .iter()
+                .any(|bot| bot.eq_ignore_ascii_case(&event.bot));
+
+        type_matches && bot_matches
+    }
+}
+
+struct Subscriber {
+    id:     u64,
+    filter: EventFilter,
+    sender: Sender<String>,
+}
+
+/// Subscribers receiving serialized [`BotEvent`]s, shared with frontend threads.
+#[derive(Clone, Default, Resource)]
+pub struct EventSubscribers {
+    next_id:     Arc<AtomicU64>,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventSubscribers {
+    /// Register a new subscriber and return its id for [`Self::unsubscribe`].
+    ///
+    /// Subscribers are also dropped when a send fails, but only once a matching event arrives.
+    pub fn subscribe(&self, filter: EventFilter, sender: Sender<String>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.subscribers
+            .lock()
+            .push(Subscriber { id, filter, sender });
+        id
+    }
+
+    /// Remove subscribers when their stream thread exits.
+    pub fn unsubscribe(&self, ids: &[u64]) {
+        self.subscribers
+            .lock()
+            .retain(|subscriber| !ids.contains(&subscriber.id));
+    }
+}
+
+impl BotEventPlugin {
+    pub fn handle_add_entity_packets(
+        mut packet_events: MessageReader<ReceiveGamePacketEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<(&TabList, &GameProfileComponent)>,
+    ) {
+        for event in packet_events.read() {
+            let ClientboundGamePacket::AddEntity(packet) = event.packet.as_ref() else {
+                continue;
+            };
+
+            if packet.entity_type != EntityKind::Player {
+                continue;
+            }
+
+            let Ok((tab_list, game_profile)) = query.get(event.entity) else {
+                continue;
+            };
+
+            let name = tab_list
+                .iter()
+                .find(|(uuid, _)| uuid == &&packet.uuid)
+                .map(|(_, info)| info.profile.name.clone())
+                .unwrap_or_default();
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Enter {
+                    uuid: packet.uuid,
+                    name,
+                },
+            });
+        }
+    }
+
+    pub fn handle_bot_events(
+        mut bot_events: MessageReader<BotEvent>,
+        subscribers: Res<EventSubscribers>,
+    ) {
+        let mut subscribers = subscribers.subscribers.lock();
+        for event in bot_events.read() {
+            if subscribers.is_empty() {
+                continue; /* No Subscribers */
+            }
+
+            let json = match serde_json::to_string(event) {
+                Ok(json) => json,
+                Err(error) => {
+                    error!("Failed to serialize event: {error}");
+                    continue;
+                }
+            };
+
+            subscribers.retain(|subscriber| {
+                !subscriber.filter.matches(event) || subscriber.sender.send(json.clone()).is_ok()
+            });
+        }
+    }
+
+    pub fn handle_chamber_events(
+        mut chamber_events: MessageReader<ChamberEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in chamber_events.read() {
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Chamber {
+                    action:    event.kind,
+                    owner:     event.owner_uuid,
+                    block_pos: event.block_pos.to_string(),
+                },
+            });
+        }
+    }
+
+    pub fn handle_chat_received_events(
+        mut chat_events: MessageReader<ChatReceivedEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<(&GameProfileComponent, &LocalSettings)>,
+        settings: Res<GlobalSettings>,
+    ) {
+        for event in chat_events.read() {
+            let Ok((game_profile, local_settings)) = query.get(event.entity) else {
+                continue;
+            };
+
+            let profile = settings.server_profile(&local_settings.server);
+            let chat_profile = settings.chat_profile(&profile.chat_profile);
+            let (username, content) = event.packet.split_sender_and_content();
+            let whisper = if username.is_some() {
+                event.packet.is_whisper()
+            } else {
+                parse_chat_profile(&chat_profile, &content).is_some_and(|(_, _, whisper)| whisper)
+            };
+
+            if whisper {
+                continue; /* Whispers may contain passwords and verification codes */
+            }
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Chat {
+                    message: event.packet.message().to_string(),
+                },
+            });
+        }
+    }
+
+    pub fn handle_cmd_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in cmd_events.read() {
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let bot = event
+                .entity
+                .and_then(|entity| query.get(entity).ok())
+                .map(|game_profile| game_profile.name.clone())
+                .unwrap_or_default();
+
+            bot_events.write(BotEvent {
+                bot,
+                kind: BotEventKind::Command {
+                    source:  str!(event.source.name()),
+                    sender:  event.sender.to_string(),
+                    command: str!(event.cmd.cmd().aliases()[0]),
+                    args:    if is_redacted(event.cmd) && !event.args.is_empty() {
+                        vec![str!(REDACTED)]
+                    } else {
+                        event.args.iter().cloned().collect()
+                    },
+                },
+            });
+        }
+    }
+
+    pub fn handle_disconnect_events(
+        mut disconnect_events: MessageReader<DisconnectEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in disconnect_events.read() {
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Leave {
+                    reason: event
+                        .reason
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                },
+            });
+        }
+    }
+
+    pub fn handle_login_packets(
+        mut login_events: MessageReader<ReceiveLoginPacketEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in login_events.read() {
+            let ClientboundLoginPacket::LoginFinished(_) = event.packet.as_ref() else {
+                continue;
+            };
+
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Join,
+            });
+        }
+    }
+
+    pub fn handle_msg_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageReader<MsgEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        mut redacted: Local<Vec<(CmdSource, Instant)>>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        /* Commands are always read first, so their responses can't arrive before them */
+        for event in cmd_events.read() {
+            if event.is_primary() && is_redacted(event.cmd) {
+                redacted.push((event.source.clone(), Instant::now()));
+            }
+        }
+
+        redacted.retain(|(_, created)| created.elapsed() < RESPONSE_WINDOW);
+
+        for event in msg_events.read() {
+            let content = if redacted
+                .iter()
+                .any(|(source, _)| source.is_same(&event.source))
+            {
+                str!(REDACTED)
+            } else {
+                event.content.clone()
+            };
+
+            let bot = event
+                .entity
+                .and_then(|entity| query.get(entity).ok())
+                .map(|game_profile| game_profile.name.clone())
+                .unwrap_or_default();
+
+            bot_events.write(BotEvent {
+                bot,
+                kind: BotEventKind::Response {
+                    source: str!(event.source.name()),
+                    status: event.status,
+                    content,
+                },
+            });
+        }
+    }
+
+    pub fn handle_pearl_goto_events(
+        mut pearl_events: MessageReader<PearlGotoEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in pearl_events.read() {
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Pearl {
+                    owner:     event.owner_uuid,
+                    block_pos: event.block_pos.to_string(),
+                },
+            });
+        }
+    }
+
+    pub fn handle_queue_events(
+        mut queue_events: MessageReader<QueueEvent>,
+        mut bot_events: MessageWriter<BotEvent>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in queue_events.read() {
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            let (position, eta) = match &event.kind {
+                QueueEventKind::Position(status) => (Some(status.position), status.eta.clone()),
+                QueueEventKind::Threshold(..) => continue, /* Duplicate */
+                QueueEventKind::Connected => (None, None),
+            };
+
+            bot_events.write(BotEvent {
+                bot:  game_profile.name.clone(),
+                kind: BotEventKind::Queue { position, eta },
+            });
+        }
+    }
+}
+
+/// Whether a command's arguments or responses may contain passwords, tokens, or codes.
+const fn is_redacted(cmd: Cmds) -> bool {
+    matches!(cmd, Cmds::Account(_) | Cmds::Verify(_) | Cmds::Whitelist(_))
+}
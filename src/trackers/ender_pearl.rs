@@ -14,6 +14,7 @@ use azalea::{
     BlockPos,
     Vec3,
 };
+use serde::Serialize;
 use uuid::Uuid;
 
 use crate::prelude::*;
@@ -23,21 +24,40 @@ pub struct EnderPearlPlugin;
 
 impl Plugin for EnderPearlPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<ResendPacketEvent>().add_systems(
-            PostUpdate,
-            (
-                Self::handle_add_entity_packet,
-                Self::handle_block_update_packets,
-                Self::handle_remove_entities_packets,
-                Self::handle_resend_packets.before(packet_listener),
-            ),
-        );
+        app.add_message::<ChamberEvent>()
+            .add_message::<ResendPacketEvent>()
+            .add_systems(
+                PostUpdate,
+                (
+                    Self::handle_add_entity_packet,
+                    Self::handle_block_update_packets,
+                    Self::handle_remove_entities_packets,
+                    Self::handle_resend_packets.before(packet_listener),
+                ),
+            );
     }
 }
 
 #[derive(Clone, Message)]
 pub struct ResendPacketEvent(ReceiveGamePacketEvent);
 
+/// A player's stasis chamber was registered, broken, or pulled.
+#[derive(Clone, Debug, Message)]
+pub struct ChamberEvent {
+    pub entity:     Entity,
+    pub owner_uuid: Uuid,
+    pub block_pos:  BlockPos,
+    pub kind:       ChamberEventKind,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChamberEventKind {
+    Registered,
+    Broken,
+    Pulled,
+}
+
 impl EnderPearlPlugin {
     pub fn handle_resend_packets(
         mut resend_packet_events: MessageReader<ResendPacketEvent>,
@@ -51,10 +71,11 @@ impl EnderPearlPlugin {
     /// # Panics
     /// Will panic if `MinecraftEntityId` out of bounds.
     /// Will panic of `Settings::save` fails.
-    #[allow(clippy::cognitive_complexity)]
+    #[allow(clippy::cognitive_complexity, clippy::too_many_arguments)]
     pub fn handle_add_entity_packet(
         mut packet_events: MessageReader<ReceiveGamePacketEvent>,
         mut query: Query<(&WorldHolder, &LocalSettings)>,
+        mut chamber_events: MessageWriter<ChamberEvent>,
         mut pearl_goto_events: MessageWriter<PearlGotoEvent>,
         mut resend_packet_events: MessageWriter<ResendPacketEvent>,
         mut stasis_chambers: ResMut<StasisChambers>,
@@ -97,8 +118,10 @@ impl EnderPearlPlugin {
                 entity_id: packet.id,
                 owner_uuid,
                 location: local_settings.auto_pearl.location.clone(),
+                server: local_settings.server.clone(),
             };
 
+            let is_new = !stasis_chambers.0.contains_key(&packet.uuid);
             stasis_chambers
                 .0
                 .entry(packet.uuid)
@@ -117,11 +140,22 @@ impl EnderPearlPlugin {
                 continue; /* Don't pull random unknown pearls */
             }
 
+            if is_new {
+                chamber_events.write(ChamberEvent {
+                    entity: event.entity,
+                    owner_uuid,
+                    block_pos,
+                    kind: ChamberEventKind::Registered,
+                });
+            }
+
             let limit = local_settings.auto_pearl.pearl_limit;
             let count = stasis_chambers
                 .0
                 .values()
-                .filter(|chamber| chamber.location == local_settings.auto_pearl.location)
+                .filter(|chamber| {
+                    chamber.is_at(&local_settings.server, &local_settings.auto_pearl.location)
+                })
                 .filter(|chamber| chamber.owner_uuid == owner_uuid)
                 .count();
 
@@ -130,7 +164,10 @@ impl EnderPearlPlugin {
                 msg_events.write(MsgEvent {
                     entity: Some(event.entity),
                     sender: CmdSender::Minecraft(owner_uuid),
-                    source: CmdSource::Minecraft(None),
+                    source: CmdSource::new(MinecraftFrontend {
+                        priority: ChatPriority::Notification,
+                        ..Default::default()
+                    }),
                     status: 402,
                     content: format!(
                         "Your free trial has expired, please purchase WinRAR license: Max {limit} pearls"
@@ -150,13 +187,19 @@ impl EnderPearlPlugin {
     /// Will panic of `Settings::save` fails.
     pub fn handle_block_update_packets(
         mut packet_events: MessageReader<ReceiveGamePacketEvent>,
+        mut chamber_events: MessageWriter<ChamberEvent>,
         mut stasis_chambers: ResMut<StasisChambers>,
+        query: Query<&LocalSettings>,
     ) {
         for event in packet_events.read() {
             let ClientboundGamePacket::BlockUpdate(packet) = event.packet.as_ref() else {
                 continue;
             };
 
+            let Ok(local_settings) = query.get(event.entity) else {
+                continue;
+            };
+
             if let Some(open) = packet.block_state.property::<Open>()
                 && open
             {
@@ -168,8 +211,17 @@ impl EnderPearlPlugin {
                 .clone()
                 .iter()
                 .filter(|(_, chamber)| chamber.block_pos == packet.pos)
-                .for_each(|(id, _)| {
+                .filter(|(_, chamber)| chamber.server == local_settings.server)
+                .for_each(|(id, chamber)| {
                     stasis_chambers.0.remove(id);
+                    if chamber.owner_uuid != Uuid::max() && packet.block_state.is_air() {
+                        chamber_events.write(ChamberEvent {
+                            entity:     event.entity,
+                            owner_uuid: chamber.owner_uuid,
+                            block_pos:  chamber.block_pos,
+                            kind:       ChamberEventKind::Broken,
+                        });
+                    }
                 });
 
             stasis_chambers
@@ -182,12 +234,12 @@ impl EnderPearlPlugin {
     /// Will panic of `Settings::save` fails.
     pub fn handle_remove_entities_packets(
         mut packet_events: MessageReader<ReceiveGamePacketEvent>,
-        mut player_positions: Query<&Position>,
+        mut player_positions: Query<(&Position, &LocalSettings)>,
         mut stasis_chambers: ResMut<StasisChambers>,
         global_settings: Res<GlobalSettings>,
     ) {
         for event in packet_events.read() {
-            let Ok(position) = player_positions.get_mut(event.entity) else {
+            let Ok((position, local_settings)) = player_positions.get_mut(event.entity) else {
                 continue;
             };
 
@@ -203,7 +255,8 @@ impl EnderPearlPlugin {
                 let distance_sqr = chamber_pos.distance_squared_to(**position);
 
                 !(packet.entity_ids.contains(&chamber.entity_id)
-                    && distance_sqr <= view_distance_sqr)
+                    && distance_sqr <= view_distance_sqr
+                    && chamber.server == local_settings.server)
             });
 
             stasis_chambers
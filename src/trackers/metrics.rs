@@ -0,0 +1,376 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    time::{Duration, Instant},
+};
+
+use azalea::{
+    app::{App, Plugin, PostUpdate},
+    ecs::prelude::*,
+    entity::{metadata::Health, LocalEntity},
+    local_player::{Hunger, TabList},
+    player::GameProfileComponent,
+};
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+/// Interval between snapshots of bot gauges, scrapes are usually much slower.
+pub const GAUGE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a command waits for responses before it's no longer counted.
+pub const RESPONSE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Tracks swarm counters and gauges for the Prometheus `/metrics` route
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Metrics>().add_systems(
+            PostUpdate,
+            (
+                Self::handle_chamber_events,
+                Self::handle_gauges,
+                Self::handle_msg_events,
+            ),
+        );
+    }
+}
+
+/// Gauges of a single bot, offline bots only have `connected` set.
+#[derive(Clone, Debug, Default)]
+pub struct BotGauges {
+    pub connected: bool,
+    pub health:    Option<f32>,
+    pub food:      Option<u32>,
+    pub ping:      Option<i32>,
+    pub queue:     Option<u32>,
+}
+
+/// Counters and gauge snapshots, rendered in the Prometheus text format.
+#[derive(Default, Resource)]
+pub struct Metrics {
+    /// Bot gauges by name, refreshed every [`GAUGE_INTERVAL`].
+    bots: BTreeMap<String, BotGauges>,
+    /// Stasis chambers by server, location, and owner.
+    chambers: BTreeMap<(String, String, Uuid), u64>,
+    /// Command responses by source, command, and status code.
+    commands: BTreeMap<(&'static str, &'static str, u16), u64>,
+    /// Pearl pulls and failed pearl commands by bot and result.
+    pearls: BTreeMap<(String, &'static str), u64>,
+    /// Reconnect decisions by bot and the matched rule name, `stop`, or `gave_up`.
+    reconnects: BTreeMap<(String, String), u64>,
+    /// Commands waiting for responses, matched by their shared source.
+    pending: Vec<(CmdSource, &'static str, Instant)>,
+    updated_at: Option<Instant>,
+    webhook_failures: u64,
+}
+
+impl Metrics {
+    /// Count a reconnect decision, `reason` should have a bounded number of values.
+    pub fn reconnect(&mut self, bot: &str, reason: &str) {
+        let key = (bot.to_lowercase(), str!(reason));
+        *self.reconnects.entry(key).or_default() += 1;
+    }
+
+    /// Render every metric in the Prometheus text exposition format.
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let connected = self.bots.values().filter(|bot| bot.connected).count();
+
+        header(&mut out, "bots_connected", "gauge", "Connected bots");
+        sample(&mut out, "bots_connected", &[], connected);
+
+        header(
+            &mut out,
+            "bot_connected",
+            "gauge",
+            "Whether a bot is connected",
+        );
+        for (bot, gauges) in &self.bots {
+            let value = u8::from(gauges.connected);
+            sample(&mut out, "bot_connected", &[("bot", bot)], value);
+        }
+
+        header(&mut out, "bot_health", "gauge", "Health of a bot");
+        for (bot, gauges) in &self.bots {
+            if let Some(health) = gauges.health {
+                sample(&mut out, "bot_health", &[("bot", bot)], health);
+            }
+        }
+
+        header(&mut out, "bot_food", "gauge", "Food level of a bot");
+        for (bot, gauges) in &self.bots {
+            if let Some(food) = gauges.food {
+                sample(&mut out, "bot_food", &[("bot", bot)], food);
+            }
+        }
+
+        header(
+            &mut out,
+            "bot_ping_ms",
+            "gauge",
+            "Tab list latency of a bot",
+        );
+        for (bot, gauges) in &self.bots {
+            if let Some(ping) = gauges.ping {
+                sample(&mut out, "bot_ping_ms", &[("bot", bot)], ping);
+            }
+        }
+
+        header(
+            &mut out,
+            "queue_position",
+            "gauge",
+            "Queue position of a bot",
+        );
+        for (bot, gauges) in &self.bots {
+            if let Some(queue) = gauges.queue {
+                sample(&mut out, "queue_position", &[("bot", bot)], queue);
+            }
+        }
+
+        header(
+            &mut out,
+            "chambers",
+            "gauge",
+            "Stasis chambers by location and owner",
+        );
+        for ((server, location, owner), count) in &self.chambers {
+            let owner = owner.to_string();
+            let labels = [
+                ("server", server),
+                ("location", location),
+                ("owner", &owner),
+            ];
+            sample(&mut out, "chambers", &labels, count);
+        }
+
+        header(
+            &mut out,
+            "pearl_results_total",
+            "counter",
+            "Pearl pulls and failures by result",
+        );
+        for ((bot, result), count) in &self.pearls {
+            let result = str!(*result);
+            sample(
+                &mut out,
+                "pearl_results_total",
+                &[("bot", bot), ("result", &result)],
+                count,
+            );
+        }
+
+        header(
+            &mut out,
+            "commands_total",
+            "counter",
+            "Command responses by source and status",
+        );
+        for ((source, command, status), count) in &self.commands {
+            let (source, command, status) = (str!(*source), str!(*command), status.to_string());
+            let labels = [
+                ("source", &source),
+                ("command", &command),
+                ("status", &status),
+            ];
+            sample(&mut out, "commands_total", &labels, count);
+        }
+
+        header(
+            &mut out,
+            "reconnects_total",
+            "counter",
+            "Reconnects by rule name, stops, and give-ups",
+        );
+        for ((bot, reason), count) in &self.reconnects {
+            sample(
+                &mut out,
+                "reconnects_total",
+                &[("bot", bot), ("reason", reason)],
+                count,
+            );
+        }
+
+        header(
+            &mut out,
+            "webhook_failures_total",
+            "counter",
+            "Failed logger webhooks",
+        );
+        sample(
+            &mut out,
+            "webhook_failures_total",
+            &[],
+            self.webhook_failures,
+        );
+
+        out
+    }
+}
+
+impl MetricsPlugin {
+    pub fn handle_chamber_events(
+        mut chamber_events: MessageReader<ChamberEvent>,
+        mut metrics: ResMut<Metrics>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        for event in chamber_events.read() {
+            if event.kind != ChamberEventKind::Pulled {
+                continue;
+            }
+
+            let Ok(game_profile) = query.get(event.entity) else {
+                continue;
+            };
+
+            let key = (game_profile.name.to_lowercase(), "pulled");
+            *metrics.pearls.entry(key).or_default() += 1;
+        }
+    }
+
+    pub fn handle_gauges(
+        mut metrics: ResMut<Metrics>,
+        query: Query<
+            (
+                &GameProfileComponent,
+                &TabList,
+                Option<&Health>,
+                Option<&Hunger>,
+                Option<&QueueStatus>,
+            ),
+            With<LocalEntity>,
+        >,
+        stasis_chambers: Res<StasisChambers>,
+        swarm_state: Res<SwarmState>,
+        webhook: Option<Res<WebhookClient>>,
+    ) {
+        if metrics
+            .updated_at
+            .is_some_and(|updated_at| updated_at.elapsed() < GAUGE_INTERVAL)
+        {
+            return;
+        }
+
+        /* Bots that disconnected are only known to the reconnect records */
+        let mut bots = swarm_state
+            .auto_reconnect
+            .read()
+            .keys()
+            .map(|name| (name.clone(), BotGauges::default()))
+            .collect::<BTreeMap<_, _>>();
+
+        for (game_profile, tab_list, health, hunger, queue_status) in &query {
+            bots.insert(
+                game_profile.name.to_lowercase(),
+                BotGauges {
+                    connected: true,
+                    health:    health.map(|health| **health),
+                    food:      hunger.map(|hunger| hunger.food),
+                    ping:      tab_list.get(&game_profile.uuid).map(|info| info.latency),
+                    queue:     queue_status.map(|queue_status| queue_status.position),
+                },
+            );
+        }
+
+        let mut chambers = BTreeMap::new();
+        for chamber in stasis_chambers.0.values() {
+            let key = (
+                chamber.server.clone(),
+                chamber.location.clone(),
+                chamber.owner_uuid,
+            );
+            *chambers.entry(key).or_default() += 1;
+        }
+
+        metrics.bots = bots;
+        metrics.chambers = chambers;
+        metrics.updated_at = Some(Instant::now());
+        metrics.webhook_failures = webhook.map_or(0, |webhook| webhook.failures());
+    }
+
+    pub fn handle_msg_events(
+        mut cmd_events: MessageReader<CmdEvent>,
+        mut msg_events: MessageReader<MsgEvent>,
+        mut metrics: ResMut<Metrics>,
+        query: Query<&GameProfileComponent>,
+    ) {
+        /* Commands are always read first, so their responses can't arrive before them */
+        for event in cmd_events.read() {
+            if !event.is_primary() {
+                continue; /* Broadcast Copy */
+            }
+
+            let command = event.cmd.cmd().aliases()[0];
+            metrics
+                .pending
+                .push((event.source.clone(), command, Instant::now()));
+        }
+
+        metrics
+            .pending
+            .retain(|(_, _, created)| created.elapsed() < RESPONSE_WINDOW);
+
+        for event in msg_events.read() {
+            let Some(command) = metrics
+                .pending
+                .iter()
+                .find(|(source, _, _)| source.is_same(&event.source))
+                .map(|(_, command, _)| *command)
+            else {
+                continue; /* Notification */
+            };
+
+            let key = (event.source.name(), command, event.status);
+            *metrics.commands.entry(key).or_default() += 1;
+
+            if command != "pearl" || event.status == 200 {
+                continue;
+            }
+
+            let bot = event
+                .entity
+                .and_then(|entity| query.get(entity).ok())
+                .map(|game_profile| game_profile.name.to_lowercase())
+                .unwrap_or_default();
+
+            let result = match event.status {
+                403 => "forbidden",
+                404 => "not_found",
+                406 => "invalid_location",
+                409 => "shared_chamber",
+                _ => "error",
+            };
+
+            *metrics.pearls.entry((bot, result)).or_default() += 1;
+        }
+    }
+}
+
+/// Write the help and type lines of a metric, names are prefixed with `shaysbot_`.
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP shaysbot_{name} {help}");
+    let _ = writeln!(out, "# TYPE shaysbot_{name} {kind}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &String)], value: impl Display) {
+    let labels = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+
+            format!("{key}=\"{value}\"")
+        })
+        .collect::<Vec<_>>();
+
+    if labels.is_empty() {
+        let _ = writeln!(out, "shaysbot_{name} {value}");
+    } else {
+        let _ = writeln!(out, "shaysbot_{name}{{{}}} {value}", labels.join(","));
+    }
+}
@@ -3,9 +3,12 @@ use azalea::app::{PluginGroup, PluginGroupBuilder};
 use crate::prelude::*;
 
 pub mod block_state;
+pub mod bot_event;
 pub mod ender_pearl;
 pub mod game_tick;
+pub mod metrics;
 pub mod player_profile;
+pub mod queue;
 
 pub struct TrackersPluginGroup;
 
@@ -13,8 +16,11 @@ impl PluginGroup for TrackersPluginGroup {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
             .add(BlockStatePlugin)
+            .add(BotEventPlugin)
             .add(EnderPearlPlugin)
             .add(GameTickPlugin)
+            .add(MetricsPlugin)
             .add(PlayerProfilePlugin)
+            .add(QueuePlugin)
     }
 }
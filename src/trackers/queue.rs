@@ -0,0 +1,190 @@
+use std::time::SystemTime;
+
+use azalea::{
+    app::{App, Plugin, Update},
+    client_chat::ChatReceivedEvent,
+    ecs::prelude::*,
+    packet::game::ReceiveGamePacketEvent,
+    protocol::packets::game::ClientboundGamePacket,
+};
+use serde::Serialize;
+
+use crate::prelude::*;
+
+/// Tracks 2b2t queue position and ETA
+pub struct QueuePlugin;
+
+impl Plugin for QueuePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<QueueEvent>().add_systems(
+            Update,
+            (
+                Self::handle_chat_received_events,
+                Self::handle_tab_list_packets,
+            ),
+        );
+    }
+}
+
+/// Queue position and ETA of a bot, removed once it reaches the server.
+#[derive(Clone, Component, Debug, Serialize)]
+pub struct QueueStatus {
+    pub position:   u32,
+    pub eta:        Option<String>,
+    pub updated_at: SystemTime,
+}
+
+impl std::fmt::Display for QueueStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.eta {
+            Some(eta) => write!(f, "position {} (ETA {eta})", self.position),
+            None => write!(f, "position {}", self.position),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Message)]
+pub struct QueueEvent {
+    pub entity: Entity,
+    pub kind:   QueueEventKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum QueueEventKind {
+    /// Queue position changed.
+    Position(QueueStatus),
+    /// Queue position passed one of the `queue_thresholds`.
+    Threshold(u32, QueueStatus),
+    /// Left the queue and reached the server.
+    Connected,
+}
+
+impl QueuePlugin {
+    pub fn handle_chat_received_events(
+        mut chat_events: MessageReader<ChatReceivedEvent>,
+        mut queue_events: MessageWriter<QueueEvent>,
+        mut commands: Commands,
+        query: Query<(Option<&QueueStatus>, &LocalSettings)>,
+        global_settings: Res<GlobalSettings>,
+    ) {
+        for event in chat_events.read() {
+            let Ok((queue_status, local_settings)) = query.get(event.entity) else {
+                continue;
+            };
+
+            let profile = global_settings.server_profile(&local_settings.server);
+            if !profile.queue {
+                continue; /* Queue Disabled */
+            }
+
+            /* Players could fake or clear the queue position with their own messages */
+            let chat_profile = global_settings.chat_profile(&profile.chat_profile);
+            let (username, content) = event.packet.split_sender_and_content();
+            if username.is_some() || parse_chat_profile(&chat_profile, &content).is_some() {
+                continue; /* Player Message */
+            }
+
+            let message = event.packet.message().to_string();
+            if message.contains("Connected to the server") {
+                if queue_status.is_some() {
+                    commands.entity(event.entity).remove::<QueueStatus>();
+                    queue_events.write(QueueEvent {
+                        entity: event.entity,
+                        kind:   QueueEventKind::Connected,
+                    });
+                }
+
+                continue;
+            }
+
+            let Some(new_status) = parse_queue_status(&message, queue_status) else {
+                continue;
+            };
+
+            let events =
+                queue_events_for(event.entity, queue_status, &new_status, &global_settings);
+            commands.entity(event.entity).insert(new_status);
+            queue_events.write_batch(events);
+        }
+    }
+
+    pub fn handle_tab_list_packets(
+        mut packet_events: MessageReader<ReceiveGamePacketEvent>,
+        mut queue_events: MessageWriter<QueueEvent>,
+        mut commands: Commands,
+        query: Query<(Option<&QueueStatus>, &LocalSettings)>,
+        global_settings: Res<GlobalSettings>,
+    ) {
+        for event in packet_events.read() {
+            let ClientboundGamePacket::TabList(packet) = event.packet.as_ref() else {
+                continue;
+            };
+
+            let Ok((queue_status, local_settings)) = query.get(event.entity) else {
+                continue;
+            };
+
+            if !global_settings.server_profile(&local_settings.server).queue {
+                continue; /* Queue Disabled */
+            }
+
+            let header = packet.header.to_string();
+            let Some(new_status) = parse_queue_status(&header, queue_status) else {
+                continue;
+            };
+
+            let events =
+                queue_events_for(event.entity, queue_status, &new_status, &global_settings);
+            commands.entity(event.entity).insert(new_status);
+            queue_events.write_batch(events);
+        }
+    }
+}
+
+/// Parse `Position in queue: N` and `Estimated time: ...` from chat or the tab header.
+fn parse_queue_status(text: &str, previous: Option<&QueueStatus>) -> Option<QueueStatus> {
+    let (_, position) = regex_captures!(r"Position in queue: (\d+)", text)?;
+    let eta = regex_captures!(r"Estimated time: ([^\n]+)", text)
+        .map(|(_, eta)| str!(eta.trim()))
+        .or_else(|| previous.and_then(|status| status.eta.clone()));
+
+    Some(QueueStatus {
+        position: position.parse().ok()?,
+        eta,
+        updated_at: SystemTime::now(),
+    })
+}
+
+fn queue_events_for(
+    entity: Entity,
+    previous: Option<&QueueStatus>,
+    status: &QueueStatus,
+    global_settings: &GlobalSettings,
+) -> Vec<QueueEvent> {
+    if previous.is_some_and(|previous| previous.position == status.position) {
+        return Vec::new(); /* Unchanged */
+    }
+
+    let mut events = vec![QueueEvent {
+        entity,
+        kind: QueueEventKind::Position(status.clone()),
+    }];
+
+    let Some(previous) = previous else {
+        return events; /* Joined Queue */
+    };
+
+    if let Some(threshold) = global_settings
+        .queue_thresholds
+        .iter()
+        .filter(|&&threshold| status.position <= threshold && previous.position > threshold)
+        .min()
+    {
+        events.push(QueueEvent {
+            entity,
+            kind: QueueEventKind::Threshold(*threshold, status.clone()),
+        });
+    }
+
+    events
+}